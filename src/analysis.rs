@@ -0,0 +1,161 @@
+use crate::board::{Board, Frame, Piece, Rotation, GRID};
+use crate::render::{self, FRAME_COLOR};
+use crate::render::ansi::{self, Depth};
+
+use std::cmp::Reverse;
+use std::fmt::Write;
+
+/// Width of a square in the heatmaps.
+const CELL_WIDTH: usize = 5;
+/// Length of the bar of the most common orientation of a piece.
+const BAR_WIDTH: usize = 16;
+
+/// Where a piece lies, and how it is turned, across a set of solutions.
+#[derive(Clone, Debug)]
+pub struct PieceAnalysis {
+    pub piece: Piece,
+    /// Number of solutions in which the piece covers each square, indexed by square.
+    pub occupancy: [usize; GRID],
+    /// Number of solutions in which the piece is placed in each of its distinct orientations, most common first.
+    pub orientations: Vec<((Rotation, bool), usize)>,
+}
+
+/// Placements of every piece across a set of solutions.
+#[derive(Clone, Debug)]
+pub struct Analysis {
+    pub frame: Frame,
+    pub solutions: usize,
+    /// One for each piece of the frame, in the order of [`Frame::pieces`].
+    pub pieces: Vec<PieceAnalysis>,
+}
+
+impl Analysis {
+    /// Counts the squares covered by, and orientations of, each piece on `boards`.
+    ///
+    /// The frame is that of the first board, and boards which are of another frame
+    /// or do not consist of valid placements are skipped.
+    pub fn new(boards: &[Board]) -> Self {
+        let frame = boards.first().map_or(Frame::default(), Board::frame);
+        let mut pieces = frame.pieces().into_iter().map(|piece| PieceAnalysis {
+            piece,
+            occupancy: [0; GRID],
+            orientations: piece.orientations().into_iter().map(|orientation| (orientation, 0)).collect(),
+        }).collect::<Vec<_>>();
+        let order = frame.pieces();
+        let index = |piece: Piece| order.iter().position(|&other| other == piece);
+
+        let mut solutions = 0;
+        for board in boards.iter().filter(|board| board.frame() == frame) {
+            let Ok(placements) = board.placements() else { continue; };
+            solutions += 1;
+
+            for square in frame.squares() {
+                if let Some(i) = board.piece_at(square).and_then(index) {
+                    pieces[i].occupancy[square as usize] += 1;
+                }
+            }
+            for placement in placements {
+                let Some(i) = index(placement.piece) else { continue; };
+                let orientations = &mut pieces[i].orientations;
+                if let Some((_, count)) = orientations.iter_mut()
+                    .find(|(orientation, _)| *orientation == (placement.rotation, placement.mirror))
+                {
+                    *count += 1;
+                }
+            }
+        }
+
+        for piece in pieces.iter_mut() {
+            piece.orientations.sort_by_key(|&(orientation, count)| (Reverse(count), orientation));
+        }
+
+        Analysis { frame, solutions, pieces }
+    }
+
+    /// A heatmap of the squares covered by each piece, beside a histogram of its orientations,
+    /// colored if `depth` is given and drawn with ASCII characters only if `ascii`.
+    pub fn report(&self, depth: Option<Depth>, ascii: bool) -> String {
+        let mut report = format!("{} solutions\n", self.solutions);
+        for piece in self.pieces.iter() {
+            let _ = write!(report, "\n{}\n", piece.piece);
+            let heatmap = piece.heatmap(self.frame, self.solutions, depth);
+            let histogram = piece.histogram(self.solutions, ascii);
+            for (i, line) in heatmap.iter().enumerate() {
+                let _ = writeln!(report, "{line}    {}", histogram.get(i).map_or("", String::as_str));
+            }
+        }
+        report.lines().map(str::trim_end).collect::<Vec<_>>().join("\n")
+    }
+
+    /// The counts as CSV, with a row for each square and each orientation of each piece.
+    pub fn csv(&self) -> String {
+        let mut csv = String::from("piece,kind,value,count\n");
+        for piece in self.pieces.iter() {
+            let letter = piece.piece.letter();
+            for square in self.frame.squares() {
+                let _ = writeln!(csv, "{letter},square,{square},{}", piece.occupancy[square as usize]);
+            }
+            for &(orientation, count) in piece.orientations.iter() {
+                let _ = writeln!(csv, "{letter},orientation,{},{count}", orientation_label(orientation));
+            }
+        }
+        csv
+    }
+}
+
+impl PieceAnalysis {
+    /// Lines of the frame, with the label of each square above the share of solutions in which the piece covers it.
+    fn heatmap(&self, frame: Frame, solutions: usize, depth: Option<Depth>) -> Vec<String> {
+        let most = self.occupancy.iter().copied().max().unwrap_or(0).max(1);
+        let color = render::piece_color(frame, self.piece);
+
+        let mut lines = Vec::new();
+        for row in 0..frame.rows() as isize {
+            let (mut labels, mut shares) = (String::new(), String::new());
+            for col in 0..frame.cols() as isize {
+                let Some(square) = frame.square_at(row, col) else {
+                    labels.push_str(&" ".repeat(CELL_WIDTH));
+                    shares.push_str(&" ".repeat(CELL_WIDTH));
+                    continue;
+                };
+
+                let count = self.occupancy[square as usize];
+                let label = format!("{:^CELL_WIDTH$}", square.to_string());
+                let share = format!("{:^CELL_WIDTH$}", percent(count, solutions));
+                match depth {
+                    Some(depth) => {
+                        let heat = FRAME_COLOR.blend(color, count as f32 / most as f32);
+                        labels.push_str(&ansi::paint(&label, heat, depth));
+                        shares.push_str(&ansi::paint(&share, heat, depth));
+                    }
+                    None => {
+                        labels.push_str(&label);
+                        shares.push_str(&share);
+                    }
+                }
+            }
+            lines.push(labels);
+            lines.push(shares);
+        }
+        lines
+    }
+
+    /// Lines with a bar for each orientation of the piece, as long as the share of solutions in which it is placed so.
+    fn histogram(&self, solutions: usize, ascii: bool) -> Vec<String> {
+        let most = self.orientations.first().map_or(0, |&(_, count)| count).max(1);
+        let block = if ascii { "#" } else { "█" };
+        self.orientations.iter().map(|&(orientation, count)| {
+            let bar = block.repeat((count * BAR_WIDTH).div_ceil(most));
+            format!("{:<3} {bar:<BAR_WIDTH$} {:>4}", orientation_label(orientation), percent(count, solutions))
+        }).collect()
+    }
+}
+
+/// Rotation and mirroring as written in placements, e.g. `1m`.
+fn orientation_label((rotation, mirror): (Rotation, bool)) -> String {
+    format!("{}{}", rotation as u8, if mirror { "m" } else { "" })
+}
+
+fn percent(count: usize, total: usize) -> String {
+    format!("{:.0}%", 100.0 * count as f64 / total.max(1) as f64)
+}
@@ -3,18 +3,55 @@ pub mod square;
 pub mod piece;
 pub mod placement;
 pub mod compact;
+pub mod frame;
 
 pub use self::path::*;
 pub use self::square::*;
 pub use self::piece::*;
 pub use self::placement::*;
+pub use self::frame::*;
 
 use Status::*;
 
 use std::fmt;
 
+/// Number of square indices, those of every frame's squares being below it.
+pub const GRID: usize = 56;
+
 #[derive(Clone, Eq, Ord, PartialEq, PartialOrd, Debug)]
-pub struct Board([Status; 45]);
+pub struct Board {
+    frame: Frame,
+    status: [Status; GRID],
+}
+
+/// Reason a partly filled board cannot be completed to a solution for some date.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum DeadEnd {
+    /// A square of the date is covered.
+    DateCovered(Square),
+    /// The empty region of these squares cannot be filled exactly by any of the pieces left.
+    Region(Vec<Square>),
+    /// Each empty region could be filled, but not all of them at once by the pieces left.
+    Regions,
+    /// No arrangement of the pieces left fills the board.
+    NoSolution,
+}
+
+impl fmt::Display for DeadEnd {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeadEnd::DateCovered(square) => write!(f, "{square} is part of the date but is covered"),
+            DeadEnd::Region(squares) if squares.len() == 1 => {
+                write!(f, "the empty square {} cannot be filled by the pieces left", squares[0])
+            }
+            DeadEnd::Region(squares) => write!(
+                f, "the region of {} empty squares including {} cannot be filled by the pieces left", squares.len(), squares[0]
+            ),
+            DeadEnd::Regions => write!(f, "the empty regions cannot all be filled by the pieces left"),
+            DeadEnd::NoSolution => write!(f, "no arrangement of the pieces left fills the board"),
+        }
+    }
+}
 
 #[derive(Copy, Clone, Eq, Ord, PartialEq, PartialOrd, Debug)]
 enum Status {
@@ -25,69 +62,158 @@ enum Status {
 
 impl Default for Board {
     fn default() -> Self {
-        let mut status = [Empty; 45];
-        status[6] = Nonexistent;
-        status[13] = Nonexistent;
-        Board(status)
+        Board::new(Frame::default())
     }
 }
 
 impl Board {
+    /// An empty board of `frame`.
+    pub fn new(frame: Frame) -> Self {
+        let mut status = [Nonexistent; GRID];
+        for square in frame.squares() {
+            status[square as usize] = Empty;
+        }
+        Board { frame, status }
+    }
+
+    pub fn frame(&self) -> Frame {
+        self.frame
+    }
+
     pub fn place(&self, piece: Piece, start: Square, path: &Path) -> Option<Self> {
-        let mut status = self.0;
+        let mut status = self.status;
         let mut square = start;
 
-        match self.0[square as usize] {
+        match self.status[square as usize] {
             Empty => status[square as usize] = Occupied(piece),
             _ => return None,
         }
 
         for dir in path.iter() {
-            square = square.step(*dir)?;
-            match self.0[square as usize] {
+            square = self.frame.step(square, *dir)?;
+            match self.status[square as usize] {
                 Empty => status[square as usize] = Occupied(piece),
                 _ => return None,
             }
         }
 
-        Some(Board(status))
+        Some(Board { frame: self.frame, status })
+    }
+
+    pub fn apply(&self, placement: &Placement) -> Option<Self> {
+        let Placement { piece, square, rotation, mirror } = *placement;
+        self.place(piece, square, &Path::from_orientation(piece, rotation, mirror))
+    }
+
+    pub fn contains(&self, piece: Piece) -> bool {
+        self.status.contains(&Occupied(piece))
+    }
+
+    /// Cells of the grid not left empty, bit `i` standing for cell `i` as numbered by [`Frame::position`].
+    pub fn occupancy(&self) -> u64 {
+        self.frame.squares().into_iter()
+            .filter(|&square| self.status[square as usize] == Empty)
+            .filter_map(|square| self.frame.position(square))
+            .fold(!0, |bits, position| bits & !(1 << position))
     }
 
     pub fn solved_for(&self) -> Option<Date> {
-        let mut status = self.0.iter();
-        
-        let month = status.position(|&sq| sq == Empty)? as u8;
-        let day = 1 + month + status.position(|&sq| sq == Empty)? as u8;
-
-        let date = Date {
-            month: Square::try_from(month).ok()?,
-            day: Square::try_from(day).ok()?
-        };
-
-        match status.find(|&&sq| sq == Empty) {
-            None if date.is_valid() => Some(date),
-            _ => None,
+        let empty = self.frame.squares().into_iter()
+            .filter(|&square| self.status[square as usize] == Empty)
+            .collect::<Vec<_>>();
+        Date::from_squares(&empty, self.frame.has_weekdays())
+    }
+
+    /// Whether the pieces left could still fill the board leaving only `date` uncovered,
+    /// as far as the sizes of its empty regions tell, without searching. See [`Board::dead_end`].
+    pub fn is_completable_by_size(&self, date: Date) -> bool {
+        self.dead_end(date).is_none()
+    }
+
+    /// Whether the board can be completed to a solution for `date`, checking the sizes of its empty regions
+    /// and then searching for a solution, or `None` if the search gives up after trying `limit` placements.
+    /// See [`solutions::completion`](crate::solutions::completion).
+    pub fn is_completable(&self, date: Date, limit: usize) -> Option<bool> {
+        match crate::solutions::completion(self, date, limit) {
+            Ok(Some(_)) => Some(true),
+            Ok(None) => None,
+            Err(_) => Some(false),
+        }
+    }
+
+    /// Reason the board cannot be completed for `date`, if the date is covered or the empty regions
+    /// around it cannot be split among the pieces left by size.
+    ///
+    /// This is quick but not exhaustive, so a board may still be a dead end if `None` is returned.
+    pub fn dead_end(&self, date: Date) -> Option<DeadEnd> {
+        if let Some(square) = date.squares().into_iter().find(|&square| self.status[square as usize] != Empty) {
+            return Some(DeadEnd::DateCovered(square));
+        }
+
+        let sizes = self.frame.pieces().into_iter()
+            .filter(|&piece| !self.contains(piece))
+            .map(|piece| piece.square_count())
+            .collect::<Vec<_>>();
+        let sums = (0..1usize << sizes.len())
+            .map(|subset| sizes.iter().enumerate().filter(|&(i, _)| subset & 1 << i != 0).map(|(_, size)| size).sum())
+            .collect::<Vec<usize>>();
+
+        let regions = self.empty_regions(date);
+        if let Some(region) = regions.iter().filter(|region| !sums.contains(&region.len())).min_by_key(|region| region.len()) {
+            return Some(DeadEnd::Region(region.clone()));
+        }
+        if !split_among(&regions, sums.len() - 1, &sums) {
+            return Some(DeadEnd::Regions);
+        }
+        None
+    }
+
+    /// Connected regions of the empty squares other than those of `date`.
+    fn empty_regions(&self, date: Date) -> Vec<Vec<Square>> {
+        let date = date.squares();
+        let mut seen = vec![false; self.status.len()];
+        let mut regions = Vec::new();
+
+        for start in Square::squares() {
+            if seen[start as usize] || self.status[start as usize] != Empty || date.contains(&start) {
+                continue;
+            }
+
+            seen[start as usize] = true;
+            let mut region = vec![start];
+            let mut i = 0;
+            while i < region.len() {
+                for dir in [Direction::Up, Direction::Down, Direction::Left, Direction::Right] {
+                    let Some(square) = self.frame.step(region[i], dir) else { continue; };
+                    if !seen[square as usize] && self.status[square as usize] == Empty && !date.contains(&square) {
+                        seen[square as usize] = true;
+                        region.push(square);
+                    }
+                }
+                i += 1;
+            }
+            regions.push(region);
         }
+
+        regions
     }
 
     fn check_placement(&self, piece: Piece, start: Square) -> Option<(Rotation, bool)> {
-        if self.0[start as usize] != Occupied(piece) {
+        if self.status[start as usize] != Occupied(piece) {
             return None;
         }
 
-        for &mirror in if piece.mirror_symmetric() { [false].iter() } else { [false, true].iter() } {
-            for rotation in Rotation::all_by_symmetry(piece.rotational_symmetry()) {
-                let path = Path::from_orientation(piece, rotation, mirror);
-                let mut square = start;
-                'walk: {
-                    for &dir in path.iter() {
-                        match square.step(dir) {
-                            Some(new_square) if self.0[new_square as usize] == Occupied(piece) => { square = new_square; }
-                            _ => { break 'walk; }
-                        };
-                    }
-                    return Some((rotation, mirror));
+        for (rotation, mirror) in piece.orientations() {
+            let path = Path::from_orientation(piece, rotation, mirror);
+            let mut square = start;
+            'walk: {
+                for &dir in path.iter() {
+                    match self.frame.step(square, dir) {
+                        Some(new_square) if self.status[new_square as usize] == Occupied(piece) => { square = new_square; }
+                        _ => { break 'walk; }
+                    };
                 }
+                return Some((rotation, mirror));
             }
         }
 
@@ -95,36 +221,40 @@ impl Board {
     } 
 
     pub fn placements(&self) -> Result<Vec<Placement>, PlacementError> {
-        if self.0[6] != Nonexistent || self.0[13] != Nonexistent {
+        let frame = Board::new(self.frame);
+        if self.status.iter().zip(frame.status.iter()).any(|(&status, &empty)| (status == Nonexistent) != (empty == Nonexistent)) {
             return Err(PlacementError);
         }
 
-        if self.0.iter().filter(|&&status| status == Nonexistent).count() > 2 {
+        let pieces = self.frame.pieces();
+        if self.status.iter().any(|status| matches!(status, Occupied(piece) if !pieces.contains(piece))) {
             return Err(PlacementError);
         }
 
-        let mut placed = [false; 8];
-        for piece in Piece::pieces() {
-            let num_squares = self.0.iter().filter(|&&status| status == Occupied(piece)).count();
+        let index = |piece: Piece| pieces.iter().position(|&other| other == piece).expect("piece should be of the frame");
+
+        let mut placed = vec![false; pieces.len()];
+        for &piece in pieces.iter() {
+            let num_squares = self.status.iter().filter(|&&status| status == Occupied(piece)).count();
             match num_squares {
                 0 => {}
-                n if n == piece.square_count() => { placed[piece as usize] = true; }
+                n if n == piece.square_count() => { placed[index(piece)] = true; }
                 _ => { return Err(PlacementError); }
             }
         }
 
-        let mut placements = [None; 8];
+        let mut placements = vec![None; pieces.len()];
         for square in Square::squares() {
-            if let Occupied(piece) = self.0[square as usize] {
-                if let (None, Some((rotation, mirror))) = (placements[piece as usize], self.check_placement(piece, square)) {
-                    placements[piece as usize] = Some((square, rotation, mirror));
+            if let Occupied(piece) = self.status[square as usize] {
+                if let (None, Some((rotation, mirror))) = (placements[index(piece)], self.check_placement(piece, square)) {
+                    placements[index(piece)] = Some((square, rotation, mirror));
                 }
             }
         }
 
         let mut result = Vec::new();
-        for piece in Piece::pieces() {
-            match (placed[piece as usize], placements[piece as usize]) {
+        for &piece in pieces.iter() {
+            match (placed[index(piece)], placements[index(piece)]) {
                 (true, Some((square, rotation, mirror))) => {
                     result.push(Placement { square, piece, rotation, mirror });
                 }
@@ -135,120 +265,217 @@ impl Board {
         Ok(result)
     }
 
-    fn get_statuses_by_corner(&self, row: usize, col: usize) -> (Status, Status, Status, Status) {
-        let (mut a, mut b, mut c, mut d) = (Nonexistent, Nonexistent, Nonexistent, Nonexistent);
+    /// Rows of the frame, with each square shown as the letter of the piece covering it,
+    /// `.` if uncovered or a space if outside the frame.
+    pub fn letter_grid(&self) -> Vec<Vec<char>> {
+        (0..self.frame.rows())
+            .map(|row| (0..self.frame.cols()).map(|col| match self.status_at(row, col) {
+                Empty => '.',
+                Nonexistent => ' ',
+                Occupied(piece) => piece.letter(),
+            }).collect())
+            .collect()
+    }
 
-        let square = col + 7*row;
-        if row <= 7 && col <= 7 {
-            if row > 0 && col > 0 && square < 53 { a = self.0[square - 8]; }
-            if row > 0 && col < 7 && square < 52 { b = self.0[square - 7]; }
-            if row < 7 && col > 0 && square < 46 { c = self.0[square - 1]; }
-            if row < 7 && col < 7 && square < 45 { d = self.0[square];     }
+    /// Piece covering `square`, if any.
+    pub fn piece_at(&self, square: Square) -> Option<Piece> {
+        match self.status[square as usize] {
+            Occupied(piece) => Some(piece),
+            _ => None,
+        }
+    }
+
+    /// Unit segments between corners of squares, given as (row, column) pairs, which separate
+    /// squares covered by different pieces, or squares of the frame from those outside it.
+    pub fn edges(&self) -> Vec<((usize, usize), (usize, usize))> {
+        let (rows, cols) = (self.frame.rows(), self.frame.cols());
+        let mut edges = Vec::new();
+        for row in 0..=rows {
+            for col in 0..=cols {
+                let (_, b, c, d) = self.get_statuses_by_corner(row, col);
+                if b != d && col < cols {
+                    edges.push(((row, col), (row, col + 1)));
+                }
+                if c != d && row < rows {
+                    edges.push(((row, col), (row + 1, col)));
+                }
+            }
+        }
+        edges
+    }
+
+    fn status_at(&self, row: usize, col: usize) -> Status {
+        match self.frame.square_at(row as isize, col as isize) {
+            Some(square) => self.status[square as usize],
+            None => Nonexistent,
         }
+    }
+
+    fn get_statuses_by_corner(&self, row: usize, col: usize) -> (Status, Status, Status, Status) {
+        let a = if row > 0 && col > 0 { self.status_at(row - 1, col - 1) } else { Nonexistent };
+        let b = if row > 0            { self.status_at(row - 1, col)     } else { Nonexistent };
+        let c = if col > 0            { self.status_at(row,     col - 1) } else { Nonexistent };
+        let d = self.status_at(row, col);
 
         (a, b, c, d)
     }
+
+    /// Box-drawing character joining the lines leaving a corner in the given directions.
+    fn corner(up: bool, down: bool, left: bool, right: bool) -> char {
+        match (up, down, left, right) {
+            (false, false, false, false) => ' ',
+            (true,  true,  false, false) => '│',
+            (false, false, true,  true ) => '─',
+            (false, true,  false, true ) => '┌',
+            (false, true,  true,  false) => '┐',
+            (true,  false, false, true ) => '└',
+            (true,  false, true,  false) => '┘',
+            (false, true,  true,  true ) => '┬',
+            (true,  true,  false, true ) => '├',
+            (true,  true,  true,  false) => '┤',
+            (true,  false, true,  true ) => '┴',
+            (true,  true,  true,  true ) => '┼',
+            (true,  false, false, false) | (false, true, false, false) => '│',
+            (false, false, true,  false) | (false, false, false, true) => '─',
+        }
+    }
+
+    /// Lines of a drawing of the board, given the characters per square of each line,
+    /// such that each square is drawn from (`top`, `left`) of its top left corner.
+    fn canvas(&self, width: usize, height: usize, top: usize, left: usize, cell: (usize, usize)) -> Vec<Vec<char>> {
+        let (rows, cols) = (self.frame.rows(), self.frame.cols());
+        let mut canvas = vec![vec![' '; width]; height];
+
+        for row in 0..=rows {
+            for col in 0..=cols {
+                let (a, b, c, d) = self.get_statuses_by_corner(row, col);
+                let (y, x) = (top + cell.1 * row, left + cell.0 * col);
+                canvas[y][x] = Board::corner(a != b, c != d, a != c, b != d);
+                if b != d && col < cols {
+                    canvas[y][x + 1..x + cell.0].fill('─');
+                }
+                if c != d && row < rows {
+                    for line in canvas[y + 1..y + cell.1].iter_mut() {
+                        line[x] = '│';
+                    }
+                }
+            }
+        }
+
+        canvas
+    }
+}
+
+/// Joins the lines of `canvas`, without trailing spaces.
+fn join(canvas: &[Vec<char>]) -> String {
+    canvas.iter()
+        .map(|line| line.iter().collect::<String>().trim_end().to_string())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Whether `regions` can each be filled by a different subset of the pieces in `pieces`, using all of them,
+/// where `sums` holds the total size of each subset of pieces.
+fn split_among(regions: &[Vec<Square>], pieces: usize, sums: &[usize]) -> bool {
+    let Some((region, rest)) = regions.split_first() else { return pieces == 0; };
+
+    let mut subset = pieces;
+    loop {
+        if sums[subset] == region.len() && split_among(rest, pieces & !subset, sums) {
+            return true;
+        }
+        if subset == 0 {
+            return false;
+        }
+        subset = (subset - 1) & pieces;
+    }
 }
 
 impl fmt::Display for Board {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        writeln!(f, "╭───────────────────────────╮")?;
-
-        for row in 0..=7 {
-            write!(f, "│ ")?;
-            for col in 0..=7 {
-                write!(f, "{}", match self.get_statuses_by_corner(row, col) {
-                    (Nonexistent, Nonexistent, Nonexistent, Nonexistent) => if row == 7 { "    " } else { "" },
-
-                    (Nonexistent, Nonexistent, _, Nonexistent) => "┐ ",
-                    (_, Nonexistent, Nonexistent, Nonexistent) => "┘ ",
-
-                    (a, Nonexistent, b, Nonexistent) if a == b => "│ ",
-                    (_, Nonexistent, _, Nonexistent) => "┤ ",
-
-                    (a, b, c, d) if a == b && a == c && a == d => "    ",
-                    (a, b, c, d) if a == b && c == d => "────",
-                    (a, b, c, d) if a == c && b == d => "│   ",
-                    
-                    (a, b, c, _) if a == b && a == c => "┌───",
-                    (a, b, _, d) if a == b && a == d => "┐   ",
-                    (a, _, c, d) if a == c && a == d => "└───",
-                    (_, b, c, d) if b == c && b == d => "┘   ",
-                    
-                    (a, b, _, _) if a == b => "┬───",
-                    (a, _, c, _) if a == c => "├───",
-                    (_, b, _, d) if b == d => "┤   ",
-                    (_, _, c, d) if c == d => "┴───",
-                    
-                    _ => "┼───",
-                })?;
+        let (rows, cols) = (self.frame.rows(), self.frame.cols());
+        let (width, height) = (4*cols + 5, 2*rows + 3);
+        let mut canvas = self.canvas(width, height, 1, 2, (4, 2));
+
+        for square in self.frame.squares() {
+            if let (Empty, Some((row, col))) = (self.status[square as usize], self.frame.coordinates(square)) {
+                let (y, x) = (2 + 2*row, 3 + 4*col);
+                let label = format!("{square:^3}");
+                canvas[y].splice(x..x + 3, label.chars());
             }
-            writeln!(f, "│")?; 
-
-            if row < 7 {
-                write!(f, "│ ")?;
-                for col in 0..=7 {
-                    match self.get_statuses_by_corner(row, col) {
-                        _ if row < 2 && col == 6 => (),
-                        _ if row == 6 && col == 7 => write!(f, "  ")?,
-                        _ if col == 7 => write!(f, "│ ")?,
-                        (_, _, a, b) => {
-                            if a == b { write!(f, " ")?; }
-                            else { write!(f, "│")?; }
-                            if b == Empty {
-                                if let Ok(square) = Square::try_from((col + 7*row) as u8) {
-                                    write!(f, "{:^3}", square)?;
-                                }
-                                else { write!(f, "   ")?; }
-                            }
-                            else { write!(f, "   ")?; }
-                        }
-                    };
+        }
+
+        // The outline encloses each column from its first square down to the bottom of the frame,
+        // two characters to either side of it.
+        let first_rows = (0..cols)
+            .map(|col| (0..rows).find(|&row| self.status_at(row, col) != Nonexistent))
+            .collect::<Vec<_>>();
+        let top = |x: usize| (0..cols)
+            .filter(|&col| 4*col <= x && x <= 4*col + 8)
+            .filter_map(|col| first_rows[col].map(|row| 2*row))
+            .min()
+            .unwrap_or(height - 1);
+
+        let bottom = height - 1;
+        for x in 0..width {
+            let (y, edge) = (top(x), x == 0 || x == width - 1);
+            canvas[y][x] = '─';
+            canvas[bottom][x] = '─';
+            if edge {
+                for line in canvas[y + 1..bottom].iter_mut() {
+                    line[x] = '│';
                 }
-                if row == 1 { writeln!(f, "╰───╮")?; }
-                else { writeln!(f, "│")?; }
+            }
+        }
+        canvas[top(0)][0] = '╭';
+        canvas[top(width - 1)][width - 1] = '╮';
+        canvas[bottom][0] = '╰';
+        canvas[bottom][width - 1] = '╯';
+
+        for x in 0..width - 1 {
+            let (y0, y1) = (top(x), top(x + 1));
+            if y0 < y1 {
+                canvas[y0][x] = '╮';
+                for line in canvas[y0 + 1..y1].iter_mut() {
+                    line[x] = '│';
+                }
+                canvas[y1][x] = '╰';
+            } else if y0 > y1 {
+                canvas[y1][x + 1] = '╭';
+                for line in canvas[y1 + 1..y0].iter_mut() {
+                    line[x + 1] = '│';
+                }
+                canvas[y0][x + 1] = '╯';
             }
         }
 
-        write!(f, "╰───────────────────────────────╯")?;
-
-        Ok(())
+        write!(f, "{}", join(&canvas))
     }
 }
 
+/// Replaces the box-drawing characters of `text` with `+`, `-` and `|`.
+fn to_ascii(text: &str) -> String {
+    text.chars().map(|c| match c {
+        '─' => '-',
+        '│' => '|',
+        '┌' | '┐' | '└' | '┘' | '├' | '┤' | '┬' | '┴' | '┼' | '╭' | '╮' | '╰' | '╯' => '+',
+        c => c,
+    }).collect()
+}
+
 impl Board {
+    /// The same drawing as `Display`, using only ASCII characters.
+    pub fn to_ascii_string(&self) -> String {
+        to_ascii(&self.to_string())
+    }
+
+    /// The same drawing as [`Board::to_mini_string`], using only ASCII characters.
+    pub fn to_mini_ascii_string(&self) -> String {
+        to_ascii(&self.to_mini_string())
+    }
+
     pub fn to_mini_string(&self) -> String {
-        let mut result = String::new();
-
-        for row in 0..=7 {
-            for col in 0..=7 {
-                result.push_str(match self.get_statuses_by_corner(row, col) {
-                    (Nonexistent, Nonexistent, Nonexistent, Nonexistent) => if row == 7 { "  " } else { "" },
-
-                    (Nonexistent, Nonexistent, _, Nonexistent) => "┐\n",
-                    (_, Nonexistent, Nonexistent, Nonexistent) => "┘\n",
-
-                    (a, Nonexistent, b, Nonexistent) if a == b => "│\n",
-                    (_, Nonexistent, _, Nonexistent) => "┤\n",
-
-                    (a, b, c, d) if a == b && a == c && a == d => "  ",
-                    (a, b, c, d) if a == b && c == d => "──",
-                    (a, b, c, d) if a == c && b == d => "│ ",
-                    
-                    (a, b, c, _) if a == b && a == c => "┌─",
-                    (a, b, _, d) if a == b && a == d => "┐ ",
-                    (a, _, c, d) if a == c && a == d => "└─",
-                    (_, b, c, d) if b == c && b == d => "┘ ",
-                    
-                    (a, b, _, _) if a == b => "┬─",
-                    (a, _, c, _) if a == c => "├─",
-                    (_, b, _, d) if b == d => "┤ ",
-                    (_, _, c, d) if c == d => "┴─",
-                    
-                    _ => "┼─",
-                });
-            }
-        }
-        result
+        let (rows, cols) = (self.frame.rows(), self.frame.cols());
+        join(&self.canvas(2*cols + 1, rows + 1, 0, 0, (2, 1)))
     }
 }
@@ -1,5 +1,5 @@
 use crate::board::Board;
-use crate::board::piece::Piece;
+use crate::board::frame::Frame;
 use crate::board::square::Square;
 use crate::board::path::Path;
 
@@ -8,18 +8,18 @@ use Rotation::*;
 
 #[derive(Debug)]
 pub struct CompactBoard {
-    squares: [Option<Square>; 8],
-    rotations: [Rotation; 8],
-    mirrors: [bool; 8],
+    frame: Frame,
+    squares: Vec<Option<Square>>,
+    rotations: Vec<Rotation>,
+    mirrors: Vec<bool>,
 }
 
 impl TryFrom<CompactBoard> for Board {
     type Error = PlacementError;
 
     fn try_from(cb: CompactBoard) -> Result<Self, Self::Error> {
-        let mut board = Board::default();
-        let pieces = Piece::pieces();
-        for (i, &piece) in pieces.iter().enumerate() {
+        let mut board = Board::new(cb.frame);
+        for (i, piece) in cb.frame.pieces().into_iter().enumerate() {
             if let (Some(square), rotation, mirror) = (cb.squares[i], cb.rotations[i], cb.mirrors[i]) {
                 let path = Path::from_orientation(piece, rotation, mirror);
                 board = board.place(piece, square, &path).ok_or(PlacementError)?;
@@ -33,34 +33,50 @@ impl TryFrom<Board> for CompactBoard {
     type Error = PlacementError;
 
     fn try_from(board: Board) -> Result<Self, Self::Error> {
-        let mut squares = [None; 8];
-        let mut rotations = [Zero; 8];
-        let mut mirrors = [false; 8];
-
-        for Placement { piece, square, rotation, mirror } in board.placements()? {
-            squares[piece as usize] = Some(square);
-            rotations[piece as usize] = rotation;
-            mirrors[piece as usize] = mirror;
-        }
-
-        Ok(CompactBoard { squares, rotations, mirrors })
+        CompactBoard::from_placements(board.frame(), &board.placements()?)
     }
 }
 
 impl CompactBoard {
-    pub fn to_bytes(&self) -> [u8; 9] {
-        let mut bytes = [0; 9];
+    /// A board of `frame` with `placements`, which are taken to fit together, as given by [`Board::placements`]
+    /// or found by the solver.
+    pub fn from_placements(frame: Frame, placements: &[Placement]) -> Result<Self, PlacementError> {
+        let pieces = frame.pieces();
+        let mut squares = vec![None; pieces.len()];
+        let mut rotations = vec![Zero; pieces.len()];
+        let mut mirrors = vec![false; pieces.len()];
+
+        for &Placement { piece, square, rotation, mirror } in placements {
+            let i = pieces.iter().position(|&other| other == piece).ok_or(PlacementError)?;
+            squares[i] = Some(square);
+            rotations[i] = rotation;
+            mirrors[i] = mirror;
+        }
+
+        Ok(CompactBoard { frame, squares, rotations, mirrors })
+    }
 
-        for mirror in self.mirrors {
-            bytes[0] = (bytes[0] << 1) | mirror as u8;
+    /// Number of bytes a board of `frame` is stored in: a bit for the mirroring of each of its pieces,
+    /// followed by a byte for the rotation and square of each.
+    pub fn size(frame: Frame) -> usize {
+        let pieces = frame.pieces().len();
+        pieces.div_ceil(8) + pieces
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let offset = self.mirrors.len().div_ceil(8);
+        let mut bytes = vec![0; CompactBoard::size(self.frame)];
+
+        for (i, &mirror) in self.mirrors.iter().enumerate() {
+            bytes[i / 8] |= (mirror as u8) << (7 - i % 8);
         }
 
         for (i, &rotation) in self.rotations.iter().enumerate() {
-            bytes[i+1] = (rotation as u8) << 6
+            bytes[offset + i] = (rotation as u8) << 6
         }
 
         for (i, &square) in self.squares.iter().enumerate() {
-            bytes[i+1] |= match square {
+            bytes[offset + i] |= match square {
                 None => 0b111111,
                 Some(square) => square as u8,
             }
@@ -68,20 +84,21 @@ impl CompactBoard {
 
         bytes
     }
-}
-
-impl From<[u8; 9]> for CompactBoard {
-    fn from(bytes: [u8; 9]) -> Self {
-        let mut squares = [None; 8];
-        let mut rotations = [Zero; 8];
-        let mut mirrors = [false; 8];
 
-        for i in 0..8 {
-            squares[i] = Square::try_from(bytes[i+1] & 0b111111).ok();
-            rotations[i] = Rotation::from((bytes[i+1] >> 6) & 0b11);
-            mirrors[i] = (bytes[0] >> (7 - i)) & 0b1 == 1;
+    /// Reads a board of `frame` from `bytes`, which must be [`CompactBoard::size`] long.
+    pub fn from_bytes(frame: Frame, bytes: &[u8]) -> Self {
+        let pieces = frame.pieces().len();
+        let offset = pieces.div_ceil(8);
+        let mut squares = vec![None; pieces];
+        let mut rotations = vec![Zero; pieces];
+        let mut mirrors = vec![false; pieces];
+
+        for i in 0..pieces {
+            squares[i] = Square::try_from(bytes[offset + i] & 0b111111).ok();
+            rotations[i] = Rotation::from((bytes[offset + i] >> 6) & 0b11);
+            mirrors[i] = (bytes[i / 8] >> (7 - i % 8)) & 0b1 == 1;
         }
 
-        CompactBoard { squares, rotations, mirrors }
+        CompactBoard { frame, squares, rotations, mirrors }
     }
 }
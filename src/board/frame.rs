@@ -0,0 +1,366 @@
+use crate::board::path::Direction::{self, *};
+use crate::board::piece::Piece;
+use crate::board::square::Square;
+use crate::board::GRID;
+
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{Mutex, OnceLock};
+
+/// Layout of the squares of a puzzle, along with the pieces it is played with.
+///
+/// Frames are read from a textual definition, such as those in the `frames` directory:
+/// a `name` line, a `pieces` line listing piece letters, and the rows of the grid,
+/// each giving the label of every square or `.` for a hole. Pieces other than the built-in ones
+/// are defined by `piece` lines, as read by [`Piece::from_definition`]. Everything after `#` is a comment.
+///
+/// Labels are limited to those of [`Square`], each used at most once, since squares are stored by their fixed index
+/// out of [`GRID`] wherever the frame puts them.
+///
+/// Loaded frames live for the rest of the program, so that boards can refer to them cheaply.
+/// Each distinct layout is kept once: loading a definition again, or making the same frame
+/// with [`Frame::with_pieces`] again, gives back the frame already loaded.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Frame(&'static Layout);
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash)]
+struct Layout {
+    name: String,
+    rows: usize,
+    cols: usize,
+    /// Square in each cell of the grid, row by row
+    cells: Vec<Option<Square>>,
+    /// Cell of each square, by index of the square
+    positions: Vec<Option<usize>>,
+    pieces: Vec<Piece>,
+}
+
+static CLASSIC: OnceLock<Frame> = OnceLock::new();
+static WEEKDAY: OnceLock<Frame> = OnceLock::new();
+
+/// Every frame loaded so far, the built-in ones included.
+static LOADED: Mutex<Vec<Frame>> = Mutex::new(Vec::new());
+
+impl Frame {
+    /// Months and days, covered by the eight original pieces.
+    pub fn classic() -> Frame {
+        *CLASSIC.get_or_init(|| {
+            include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/frames/classic.frame")).parse().unwrap()
+        })
+    }
+
+    /// Months, days and weekdays, covered by ten pieces.
+    pub fn weekday() -> Frame {
+        *WEEKDAY.get_or_init(|| {
+            include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/frames/weekday.frame")).parse().unwrap()
+        })
+    }
+
+    pub fn name(&self) -> &'static str {
+        &self.0.name
+    }
+
+    /// Number of rows of the grid, every square of the frame lying within them.
+    pub fn rows(&self) -> usize {
+        self.0.rows
+    }
+
+    /// Number of columns of the grid.
+    pub fn cols(&self) -> usize {
+        self.0.cols
+    }
+
+    pub fn has_weekdays(&self) -> bool {
+        self.squares().iter().any(Square::is_weekday)
+    }
+
+    pub fn contains(&self, square: Square) -> bool {
+        self.position(square).is_some()
+    }
+
+    /// Squares of the frame, in reading order.
+    pub fn squares(&self) -> Vec<Square> {
+        self.0.cells.iter().flatten().copied().collect()
+    }
+
+    /// Square at (`row`, `col`), if there is one in the frame.
+    pub fn square_at(&self, row: isize, col: isize) -> Option<Square> {
+        if !(0..self.rows() as isize).contains(&row) || !(0..self.cols() as isize).contains(&col) {
+            return None;
+        }
+        self.0.cells[col as usize + self.cols() * row as usize]
+    }
+
+    /// Cell of `square` in the grid, numbered row by row, if it is in the frame.
+    pub fn position(&self, square: Square) -> Option<usize> {
+        self.0.positions[square as usize]
+    }
+
+    /// Row and column of `square`, if it is in the frame.
+    pub fn coordinates(&self, square: Square) -> Option<(usize, usize)> {
+        self.position(square).map(|position| (position / self.cols(), position % self.cols()))
+    }
+
+    /// Square next to `square` in direction `dir`, if there is one in the frame.
+    pub fn step(&self, square: Square, dir: Direction) -> Option<Square> {
+        let (row, col) = self.coordinates(square)?;
+        let (row, col) = (row as isize, col as isize);
+        match dir {
+            Up    => self.square_at(row - 1, col),
+            Down  => self.square_at(row + 1, col),
+            Left  => self.square_at(row, col - 1),
+            Right => self.square_at(row, col + 1),
+        }
+    }
+
+    /// Pieces the frame is played with, in the order they are stored.
+    pub fn pieces(&self) -> Vec<Piece> {
+        self.0.pieces.clone()
+    }
+
+    /// Index of the color of `piece` in palettes giving one for each of [`Piece::pieces`]:
+    /// that of a built-in piece, or the next one not taken by a built-in piece of the frame for others.
+    pub fn color_index(&self, piece: Piece) -> usize {
+        if let Some(i) = Piece::pieces().iter().position(|&other| other == piece) {
+            return i;
+        }
+        let free = (0..Piece::COUNT).filter(|&i| !self.0.pieces.contains(&Piece::pieces()[i])).collect::<Vec<_>>();
+        let nth = self.0.pieces.iter().filter(|other| !other.is_built_in()).position(|&other| other == piece).unwrap_or(0);
+        free.get(nth).copied().unwrap_or(nth % Piece::COUNT)
+    }
+
+    /// The same layout played with `pieces` instead, named after the frame and the pieces.
+    ///
+    /// Like a loaded frame, the new frame lives for the rest of the program. See [`Frame`].
+    pub fn with_pieces(&self, pieces: Vec<Piece>) -> Result<Frame, FrameError> {
+        if pieces == self.0.pieces {
+            return Ok(*self);
+        }
+        let letters = pieces.iter().map(Piece::letter).collect::<String>();
+        let layout = Layout {
+            name: format!("{}-{letters}", self.name()),
+            rows: self.rows(),
+            cols: self.cols(),
+            cells: self.0.cells.clone(),
+            positions: self.0.positions.clone(),
+            pieces,
+        };
+        layout.check()?;
+        Ok(Frame::intern(layout))
+    }
+}
+
+impl Frame {
+    /// The frame of `layout`, leaked only if no frame with the same layout has been loaded before.
+    fn intern(layout: Layout) -> Frame {
+        let mut loaded = LOADED.lock().unwrap();
+        if let Some(&frame) = loaded.iter().find(|frame| *frame.0 == layout) {
+            return frame;
+        }
+        let frame = Frame(Box::leak(Box::new(layout)));
+        loaded.push(frame);
+        frame
+    }
+}
+
+impl Default for Frame {
+    fn default() -> Self {
+        Frame::classic()
+    }
+}
+
+impl fmt::Debug for Frame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Frame").field(&self.name()).finish()
+    }
+}
+
+/// Writes the definition of the frame, which parses back to the same frame.
+impl fmt::Display for Frame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "name {}", self.name())?;
+        for piece in self.0.pieces.iter().filter(|piece| !piece.is_built_in()) {
+            writeln!(f, "piece {}", piece.definition())?;
+        }
+        let letters = self.0.pieces.iter().map(|piece| piece.letter().to_string()).collect::<Vec<_>>();
+        writeln!(f, "pieces {}", letters.join(" "))?;
+        writeln!(f)?;
+        for row in self.0.cells.chunks(self.cols()) {
+            let labels = row.iter()
+                .map(|cell| format!("{:>3}", cell.map_or(".".to_string(), |square| square.to_string())))
+                .collect::<Vec<_>>();
+            writeln!(f, "{}", labels.join(" ").trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+/// Reason a frame definition is invalid, along with the line it was found on, if any.
+#[derive(Debug)]
+pub struct FrameError {
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl Error for FrameError {}
+
+impl fmt::Display for FrameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(n) => write!(f, "line {n}: {}", self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+fn error(line: Option<usize>, message: String) -> FrameError {
+    FrameError { line, message }
+}
+
+/// Most pieces a frame may be played with, the solver keeping track of every subset of them.
+const MAX_PIECES: usize = 16;
+
+/// Lines of a definition, read but not yet checked to make up a frame.
+struct Definition {
+    name: Option<String>,
+    /// Pieces of the `pieces` line, if there is one
+    pieces: Option<Vec<Piece>>,
+    /// Pieces of the `piece` lines
+    defined: Vec<Piece>,
+    /// Squares of each row of the grid, along with the line they were found on
+    grid: Vec<(usize, Vec<Option<Square>>)>,
+}
+
+impl Definition {
+    fn read(s: &str) -> Result<Self, FrameError> {
+        let mut name = None;
+        let mut listed = None;
+        let mut defined = Vec::new();
+        let mut grid = Vec::new();
+        for (i, line) in s.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default();
+            let mut words = line.split_whitespace().peekable();
+            match words.peek() {
+                None => {}
+                Some(&"name") => {
+                    name = Some(words.skip(1).collect::<Vec<_>>().join(" "));
+                }
+                Some(&"piece") => {
+                    let definition = words.skip(1).collect::<Vec<_>>().join(" ");
+                    defined.push(Piece::from_definition(&definition).map_err(|err| error(Some(i + 1), err.to_string()))?);
+                }
+                Some(&"pieces") => {
+                    listed = Some((i + 1, words.skip(1).collect::<Vec<_>>()));
+                }
+                Some(_) => {
+                    let mut row = Vec::new();
+                    for word in words {
+                        row.push(match word {
+                            "." => None,
+                            label => Some(label.parse::<Square>().map_err(|_| error(Some(i + 1), format!("unknown square `{label}`")))?),
+                        });
+                    }
+                    grid.push((i + 1, row));
+                }
+            }
+        }
+
+        // Pieces are listed by letter, so only once every piece is defined.
+        let pieces = match listed {
+            Some((line, words)) => {
+                let mut pieces = Vec::new();
+                for word in words {
+                    let piece = word.parse::<Piece>().map_err(|_| error(Some(line), format!("unknown piece `{word}`")))?;
+                    if pieces.contains(&piece) {
+                        return Err(error(Some(line), format!("piece {} is listed twice", piece.letter())));
+                    }
+                    pieces.push(piece);
+                }
+                Some(pieces)
+            }
+            None => None,
+        };
+
+        Ok(Definition { name, pieces, defined, grid })
+    }
+
+    /// Pieces to play with: those listed, or those defined if none are.
+    fn pieces(&self) -> Result<Vec<Piece>, FrameError> {
+        match &self.pieces {
+            Some(pieces) => Ok(pieces.clone()),
+            None if !self.defined.is_empty() => Ok(self.defined.clone()),
+            None => Err(error(None, "no `pieces` line".to_string())),
+        }
+    }
+}
+
+impl Layout {
+    /// Checks that the layout can be solved for dates, as far as its squares and the area of its pieces tell.
+    fn check(&self) -> Result<(), FrameError> {
+        let squares = self.cells.iter().flatten().copied().collect::<Vec<_>>();
+        if !squares.iter().any(Square::is_month) || !squares.iter().any(Square::is_day) {
+            return Err(error(None, "frame needs at least one month and one day".to_string()));
+        }
+        if self.pieces.is_empty() {
+            return Err(error(None, "frame needs at least one piece".to_string()));
+        }
+        if self.pieces.len() > MAX_PIECES {
+            return Err(error(None, format!("frame has {} pieces, more than {MAX_PIECES}", self.pieces.len())));
+        }
+        let uncovered = if squares.iter().any(Square::is_weekday) { 3 } else { 2 };
+        let area = self.pieces.iter().map(Piece::square_count).sum::<usize>();
+        if area + uncovered != squares.len() {
+            return Err(error(None, format!(
+                "pieces cover {area} squares, but {} of the {} squares should be covered", squares.len() - uncovered, squares.len()
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Frame {
+    type Err = FrameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let definition = Definition::read(s)?;
+        let pieces = definition.pieces()?;
+        let grid = definition.grid;
+        let rows = grid.len();
+        let cols = grid.iter().map(|(_, row)| row.len()).max().unwrap_or(0);
+        if rows * cols == 0 {
+            return Err(error(None, "no squares".to_string()));
+        }
+        if rows * cols > 64 {
+            return Err(error(None, format!("grid of {rows}x{cols} cells is larger than 64 cells")));
+        }
+
+        let mut cells = vec![None; rows * cols];
+        let mut positions = vec![None; GRID];
+        for (row, (line, squares)) in grid.into_iter().enumerate() {
+            for (col, square) in squares.into_iter().enumerate() {
+                let Some(square) = square else { continue; };
+                if positions[square as usize].is_some() {
+                    return Err(error(Some(line), format!("square {square} appears twice")));
+                }
+                positions[square as usize] = Some(col + cols * row);
+                cells[col + cols * row] = Some(square);
+            }
+        }
+
+        let name = definition.name.unwrap_or_else(|| "custom".to_string());
+        let layout = Layout { name, rows, cols, cells, positions, pieces };
+        layout.check()?;
+        Ok(Frame::intern(layout))
+    }
+}
+
+/// Reads a set of pieces: `piece` lines defining pieces as in a frame definition,
+/// and optionally a `pieces` line listing those to play with, which are otherwise all those defined.
+pub fn parse_pieces(s: &str) -> Result<Vec<Piece>, FrameError> {
+    let definition = Definition::read(s)?;
+    if let Some((line, _)) = definition.grid.first() {
+        return Err(error(Some(*line), "expected a `piece` or `pieces` line".to_string()));
+    }
+    definition.pieces()
+}
@@ -3,7 +3,7 @@ use crate::board::placement::Rotation;
 
 use Direction::*;
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Eq, Ord, PartialEq, PartialOrd, Debug, Hash)]
 #[repr(u8)]
 pub enum Direction {
     Up,
@@ -12,7 +12,26 @@ pub enum Direction {
     Right,
 }
 
-#[derive(Clone, Debug)]
+impl Direction {
+    /// `cell` moved one square in this direction.
+    pub fn step(self, (row, col): Cell) -> Cell {
+        match self {
+            Up    => (row - 1, col),
+            Down  => (row + 1, col),
+            Left  => (row, col - 1),
+            Right => (row, col + 1),
+        }
+    }
+}
+
+/// Square reached by a path, as (row, column) relative to where it starts.
+pub type Cell = (i32, i32);
+
+/// Steps taken from a piece's anchor through every square it covers.
+///
+/// Paths are equal when they take the same steps; paths covering the same squares by other steps
+/// share a [`Path::footprint`] instead.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
 pub struct Path(Vec<Direction>);
 
 impl From<Vec<Direction>> for Path {
@@ -57,6 +76,48 @@ impl Path {
         path.rotate(rotation)
     }
 
+    /// Squares visited, in the order they are first reached, starting from (0, 0).
+    pub fn cells(&self) -> Vec<Cell> {
+        let mut cell = (0, 0);
+        let mut cells = vec![cell];
+        for &dir in &self.0 {
+            cell = dir.step(cell);
+            if !cells.contains(&cell) {
+                cells.push(cell);
+            }
+        }
+        cells
+    }
+
+    /// Squares visited, moved so that the first row and column are 0, in reading order.
+    ///
+    /// This is the same for any paths covering the same squares up to translation,
+    /// and so tells apart the orientations of a piece.
+    pub fn footprint(&self) -> Vec<Cell> {
+        normalized(&self.cells())
+    }
+
+    /// Number of quarter turns, out of four, leaving the footprint unchanged.
+    pub fn rotational_symmetry(&self) -> u8 {
+        let footprint = self.footprint();
+        (0..4).filter(|&amount| self.rotate(Rotation::from(amount)).footprint() == footprint).count() as u8
+    }
+
+    /// Whether the mirrored path has the footprint of some turn of the path.
+    pub fn mirror_symmetric(&self) -> bool {
+        let footprint = self.footprint();
+        let mirrored = self.mirror();
+        (0..4).any(|amount| mirrored.rotate(Rotation::from(amount)).footprint() == footprint)
+    }
+
+    /// Least footprint among all turns of the path and its mirror image, the same for every orientation of a shape.
+    pub fn canonical(&self) -> Vec<Cell> {
+        [self.clone(), self.mirror()].iter()
+            .flat_map(|path| (0..4).map(|amount| path.rotate(Rotation::from(amount)).footprint()))
+            .min()
+            .unwrap_or_default()
+    }
+
     pub fn iter(&self) -> std::slice::Iter<'_, Direction> {
         self.0.iter()
     }
@@ -65,3 +126,12 @@ impl Path {
         self.0.iter_mut()
     }
 }
+
+/// `cells` moved so that the first row and column are 0, in reading order.
+pub fn normalized(cells: &[Cell]) -> Vec<Cell> {
+    let top = cells.iter().map(|cell| cell.0).min().unwrap_or(0);
+    let left = cells.iter().map(|cell| cell.1).min().unwrap_or(0);
+    let mut cells = cells.iter().map(|&(row, col)| (row - top, col - left)).collect::<Vec<_>>();
+    cells.sort_unstable();
+    cells
+}
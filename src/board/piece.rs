@@ -1,60 +1,242 @@
-use crate::board::path::{Direction::*, Path};
+use crate::board::path::{self, Cell, Direction::{self, *}, Path};
+use crate::board::placement::Rotation;
 
+use std::error::Error;
 use std::fmt;
+use std::str::FromStr;
+use std::sync::Mutex;
 
-use Piece::*;
+/// A piece, known by its letter, covering the squares visited by a path from its anchor.
+///
+/// Besides the built-in pieces, pieces can be defined by the squares they cover with [`Piece::define`].
+/// Like frames, defined pieces live for the rest of the program.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Piece(&'static Shape);
 
-#[derive(Copy, Clone, Eq, Ord, PartialEq, PartialOrd, Debug)]
-#[repr(u8)]
-pub enum Piece {
-    L, N, O, P, U, V, Y, Z,
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash)]
+struct Shape {
+    /// Position among the built-in pieces, or `u8::MAX` for defined pieces, so that built-in pieces sort first
+    rank: u8,
+    letter: char,
+    path: &'static [Direction],
+    square_count: usize,
+    rotational_symmetry: u8,
+    mirror_symmetric: bool,
 }
 
+/// Pieces defined besides the built-in ones.
+static DEFINED: Mutex<Vec<Piece>> = Mutex::new(Vec::new());
+
 impl fmt::Display for Piece {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}-piece", match self {
-            L => 'L', N => 'N', O => 'O', P => 'P',
-            U => 'U', V => 'V', Y => 'Y', Z => 'Z',
-        })
+        write!(f, "{}-piece", self.letter())
+    }
+}
+
+impl fmt::Debug for Piece {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Piece").field(&self.letter()).finish()
+    }
+}
+
+#[derive(Debug)]
+pub struct ParsePieceError;
+
+impl FromStr for Piece {
+    type Err = ParsePieceError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let mut letter = s.strip_suffix("-piece").unwrap_or(s).chars();
+        match (letter.next(), letter.next()) {
+            (Some(letter), None) => Piece::find(letter.to_ascii_uppercase()).ok_or(ParsePieceError),
+            _ => Err(ParsePieceError),
+        }
+    }
+}
+
+/// Reason a piece could not be defined.
+#[derive(Debug)]
+pub struct PieceError(String);
+
+impl Error for PieceError {}
+
+impl fmt::Display for PieceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
     }
 }
 
 impl Piece {
-    pub fn pieces() -> [Piece; 8] {
-        [L, N, O, P, U, V, Y, Z]
+    pub const L: Piece = Piece(&Shape { rank:  0, letter: 'L', path: &[Down, Down, Down, Right],     square_count: 5, rotational_symmetry: 1, mirror_symmetric: false });
+    pub const N: Piece = Piece(&Shape { rank:  1, letter: 'N', path: &[Up, Right, Up, Up],           square_count: 5, rotational_symmetry: 1, mirror_symmetric: false });
+    pub const O: Piece = Piece(&Shape { rank:  2, letter: 'O', path: &[Up, Up, Right, Down, Down],   square_count: 6, rotational_symmetry: 2, mirror_symmetric: true  });
+    pub const P: Piece = Piece(&Shape { rank:  3, letter: 'P', path: &[Up, Up, Right, Down],         square_count: 5, rotational_symmetry: 1, mirror_symmetric: false });
+    pub const U: Piece = Piece(&Shape { rank:  4, letter: 'U', path: &[Down, Right, Right, Up],      square_count: 5, rotational_symmetry: 1, mirror_symmetric: true  });
+    pub const V: Piece = Piece(&Shape { rank:  5, letter: 'V', path: &[Right, Right, Up, Up],        square_count: 5, rotational_symmetry: 1, mirror_symmetric: true  });
+    pub const Y: Piece = Piece(&Shape { rank:  6, letter: 'Y', path: &[Up, Up, Up, Down, Left],      square_count: 5, rotational_symmetry: 1, mirror_symmetric: false });
+    pub const Z: Piece = Piece(&Shape { rank:  7, letter: 'Z', path: &[Right, Down, Down, Right],    square_count: 5, rotational_symmetry: 2, mirror_symmetric: false });
+    pub const I: Piece = Piece(&Shape { rank:  8, letter: 'I', path: &[Down, Down, Down],            square_count: 4, rotational_symmetry: 2, mirror_symmetric: true  });
+    pub const J: Piece = Piece(&Shape { rank:  9, letter: 'J', path: &[Down, Down, Right],           square_count: 4, rotational_symmetry: 1, mirror_symmetric: false });
+    pub const S: Piece = Piece(&Shape { rank: 10, letter: 'S', path: &[Right, Up, Right],            square_count: 4, rotational_symmetry: 2, mirror_symmetric: false });
+}
+
+impl Piece {
+    /// Number of built-in pieces among all frames.
+    pub const COUNT: usize = 11;
+
+    /// Every built-in piece, the pentominoes and hexomino of the classic frame before the tetrominoes.
+    pub fn pieces() -> [Piece; Piece::COUNT] {
+        [Piece::L, Piece::N, Piece::O, Piece::P, Piece::U, Piece::V, Piece::Y, Piece::Z, Piece::I, Piece::J, Piece::S]
     }
 
-    pub fn rotational_symmetry(&self) -> u8 {
-        match self {
-            O | Z => 2,
-            _ => 1,
+    /// Piece of the given letter, among the built-in pieces and those defined.
+    pub fn find(letter: char) -> Option<Piece> {
+        Piece::pieces().into_iter()
+            .chain(DEFINED.lock().unwrap().iter().copied())
+            .find(|piece| piece.letter() == letter)
+    }
+
+    /// Defines the piece of `letter` covering `cells`, given as (row, column), anchored at the first in reading order.
+    ///
+    /// Defining a piece whose letter is taken gives the piece of that letter, as long as it has the same shape.
+    pub fn define(letter: char, cells: &[(i32, i32)]) -> Result<Piece, PieceError> {
+        if !letter.is_ascii_uppercase() {
+            return Err(PieceError(format!("pieces are named by an uppercase letter, not `{letter}`")));
+        }
+        if cells.is_empty() {
+            return Err(PieceError(format!("{letter}-piece covers no squares")));
+        }
+        let cells = path::normalized(cells);
+        if let Some(pair) = cells.windows(2).find(|pair| pair[0] == pair[1]) {
+            return Err(PieceError(format!("{letter}-piece covers {},{} twice", pair[0].0, pair[0].1)));
+        }
+        let path = path_through(&cells)
+            .map(Path::from)
+            .ok_or_else(|| PieceError(format!("the squares of the {letter}-piece are not connected")))?;
+
+        let mut defined = DEFINED.lock().unwrap();
+        let existing = Piece::pieces().into_iter().chain(defined.iter().copied()).find(|piece| piece.letter() == letter);
+        if let Some(piece) = existing {
+            return match Path::from(piece).canonical() == path.canonical() {
+                true => Ok(piece),
+                false => Err(PieceError(format!("{piece} is already defined with another shape"))),
+            };
         }
+
+        let piece = Piece(Box::leak(Box::new(Shape {
+            rank: u8::MAX,
+            letter,
+            path: Box::leak(path.directions().clone().into_boxed_slice()),
+            square_count: cells.len(),
+            rotational_symmetry: path.rotational_symmetry(),
+            mirror_symmetric: path.mirror_symmetric(),
+        })));
+        defined.push(piece);
+        Ok(piece)
+    }
+
+    /// Defines a piece from its definition: its letter followed by the row and column of each square it covers,
+    /// e.g. `T 0,0 0,1 0,2 1,1 2,1`. See [`Piece::define`].
+    pub fn from_definition(s: &str) -> Result<Piece, PieceError> {
+        let mut words = s.split_whitespace();
+        let mut letter = words.next().unwrap_or_default().chars();
+        let letter = match (letter.next(), letter.next()) {
+            (Some(letter), None) => letter,
+            _ => return Err(PieceError("piece definitions start with the letter of the piece".to_string())),
+        };
+        let cells = words
+            .map(|word| {
+                word.split_once(',')
+                    .and_then(|(row, col)| Some((row.parse().ok()?, col.parse().ok()?)))
+                    .ok_or_else(|| PieceError(format!("invalid square `{word}`, expected row,column")))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Piece::define(letter, &cells)
+    }
+
+    /// Definition of the piece, as read by [`Piece::from_definition`].
+    pub fn definition(&self) -> String {
+        let cells = self.cells().iter().map(|(row, col)| format!("{row},{col}")).collect::<Vec<_>>();
+        format!("{} {}", self.letter(), cells.join(" "))
+    }
+
+    /// Whether the piece is one of [`Piece::pieces`].
+    pub fn is_built_in(&self) -> bool {
+        self.0.rank != u8::MAX
+    }
+
+    pub fn letter(&self) -> char {
+        self.0.letter
+    }
+
+    pub fn rotational_symmetry(&self) -> u8 {
+        self.0.rotational_symmetry
     }
 
     pub fn mirror_symmetric(&self) -> bool {
-        matches!(self, O | U | V)
+        self.0.mirror_symmetric
     }
 
     pub fn square_count(&self) -> usize {
-        match self {
-            O => 6,
-            _ => 5,
-        }
+        self.0.square_count
+    }
+
+    /// Squares covered by the piece, as (row, column) relative to its anchor, in the order its path visits them.
+    pub fn cells(&self) -> Vec<Cell> {
+        Path::from(*self).cells()
+    }
+
+    pub fn orientations(&self) -> Vec<(Rotation, bool)> {
+        let mirrors = if self.mirror_symmetric() { vec![false] } else { vec![false, true] };
+        mirrors.into_iter()
+            .flat_map(|mirror| Rotation::all_by_symmetry(self.rotational_symmetry())
+                .into_iter()
+                .map(move |rotation| (rotation, mirror))
+            )
+            .collect()
+    }
+
+    /// Orientations placing the piece differently, worked out from its footprint in every orientation
+    /// rather than from its symmetries, keeping the first of each in the order of [`Piece::orientations`].
+    pub fn distinct_orientations(&self) -> Vec<(Rotation, bool)> {
+        let mut footprints = Vec::new();
+        [false, true].into_iter()
+            .flat_map(|mirror| (0..4).map(move |amount| (Rotation::from(amount), mirror)))
+            .filter(|&(rotation, mirror)| {
+                let footprint = Path::from_orientation(*self, rotation, mirror).footprint();
+                let new = !footprints.contains(&footprint);
+                footprints.push(footprint);
+                new
+            })
+            .collect()
     }
 }
 
 impl From<Piece> for Path {
     fn from(piece: Piece) -> Self {
-        let directions = match piece {
-            L => vec![Down, Down, Down, Right],
-            N => vec![Up, Right, Up, Up],
-            O => vec![Up, Up, Right, Down, Down],
-            P => vec![Up, Up, Right, Down],
-            U => vec![Down, Right, Right, Up],
-            V => vec![Right, Right, Up, Up],
-            Y => vec![Up, Up, Up, Down, Left],
-            Z => vec![Right, Down, Down, Right],
-        };
-        Path::from(directions)
+        Path::from(piece.0.path.to_vec())
+    }
+}
+
+/// Path from the first of `cells` through every other, stepping back over squares already visited where it must,
+/// or `None` if they are not connected.
+fn path_through(cells: &[Cell]) -> Option<Vec<Direction>> {
+    fn visit(cell: Cell, cells: &[Cell], seen: &mut Vec<Cell>, path: &mut Vec<Direction>, end: &mut usize) {
+        for (dir, back) in [(Right, Left), (Down, Up), (Left, Right), (Up, Down)] {
+            let next = dir.step(cell);
+            if cells.contains(&next) && !seen.contains(&next) {
+                seen.push(next);
+                path.push(dir);
+                *end = path.len();
+                visit(next, cells, seen, path, end);
+                path.push(back);
+            }
+        }
     }
+
+    let (mut seen, mut path, mut end) = (vec![cells[0]], Vec::new(), 0);
+    visit(cells[0], cells, &mut seen, &mut path, &mut end);
+    path.truncate(end);
+    (seen.len() == cells.len()).then_some(path)
 }
@@ -5,8 +5,9 @@ use Rotation::*;
 
 use std::error::Error;
 use std::fmt;
+use std::str::FromStr;
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Eq, Ord, PartialEq, PartialOrd, Debug, Hash)]
 #[repr(u8)]
 pub enum Rotation {
     Zero,
@@ -37,7 +38,7 @@ impl From<u8> for Rotation {
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Copy, Eq, Ord, PartialEq, PartialOrd, Debug, Hash)]
 pub struct Placement {
     pub piece: Piece,
     pub square: Square,
@@ -45,6 +46,50 @@ pub struct Placement {
     pub mirror: bool,
 }
 
+impl fmt::Display for Placement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}@{}:{}", self.piece.letter(), self.square, self.rotation as u8)?;
+        if self.mirror { write!(f, "m")?; }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct ParsePlacementError(&'static str);
+
+impl Error for ParsePlacementError {}
+
+impl fmt::Display for ParsePlacementError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for Placement {
+    type Err = ParsePlacementError;
+
+    /// Parses placements of the form `piece@square:rotation[m]`, e.g. `L@Jan:1m`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (piece, rest) = s.trim().split_once('@')
+            .ok_or(ParsePlacementError("placement must be of the form piece@square:rotation"))?;
+        let (square, orientation) = rest.split_once(':').unwrap_or((rest, "0"));
+        let (rotation, mirror) = match orientation.strip_suffix(['m', 'M']) {
+            Some(rotation) => (rotation, true),
+            None => (orientation, false),
+        };
+
+        Ok(Placement {
+            piece: piece.parse().map_err(|_| ParsePlacementError("invalid piece"))?,
+            square: square.parse().map_err(|_| ParsePlacementError("invalid square"))?,
+            rotation: match rotation.parse::<u8>() {
+                Ok(amount) if amount < 4 => Rotation::from(amount),
+                _ => Err(ParsePlacementError("rotation must be in range 0-3"))?,
+            },
+            mirror,
+        })
+    }
+}
+
 #[derive(Debug)]
 pub struct PlacementError;
 
@@ -1,9 +1,11 @@
-use crate::board::path::Direction::{self, *};
-
 use Square::*;
 
 use std::fmt;
+use std::str::FromStr;
 
+/// Label of a square of any frame.
+///
+/// Indices are how squares are stored, so they never change; they follow the layout of the weekday frame.
 #[derive(Copy, Clone, Eq, Ord, PartialEq, PartialOrd, Debug, Hash)]
 #[repr(u8)]
 pub enum Square {
@@ -13,25 +15,11 @@ pub enum Square {
     D08 = 21, D09, D10, D11, D12, D13, D14,
     D15 = 28, D16, D17, D18, D19, D20, D21,
     D22 = 35, D23, D24, D25, D26, D27, D28,
-    D29 = 42, D30, D31,
+    D29 = 42, D30, D31, Sun, Mon, Tue, Wed,
+                                  Thu = 53, Fri, Sat,
 }
 
 impl Square {
-    pub fn step(&self, dir: Direction) -> Option<Self> {
-        let square = *self as u8;
-        match (dir, square % 7, square / 7) {
-            (Up,    _, 0) => None,
-            (Left,  0, _) => None,
-            (Right, 6, _) => None,
-            _ => Square::try_from(match dir {
-                Up    => square - 7,
-                Down  => square + 7,
-                Left  => square - 1,
-                Right => square + 1,
-            }).ok()
-        }
-    }
-
     pub fn squares() -> Vec<Square> {
         vec![
             Jan, Feb, Mar, Apr, May, Jun,
@@ -41,8 +29,21 @@ impl Square {
             D15, D16, D17, D18, D19, D20, D21,
             D22, D23, D24, D25, D26, D27, D28,
             D29, D30, D31,
+            Sun, Mon, Tue, Wed, Thu, Fri, Sat,
         ]
     }
+
+    pub fn is_month(&self) -> bool {
+        *self <= Dec
+    }
+
+    pub fn is_day(&self) -> bool {
+        (D01..=D31).contains(self)
+    }
+
+    pub fn is_weekday(&self) -> bool {
+        *self >= Sun
+    }
 }
 
 #[derive(Debug)]
@@ -52,20 +53,7 @@ impl TryFrom<u8> for Square {
     type Error = IndexError;
     
     fn try_from(index: u8) -> Result<Self, Self::Error> {
-        if index == 6 || index == 13 || index > 44 {
-            Err(IndexError)
-        } else {
-            Ok(match index {
-                 0 => Jan,  1 => Feb,  2 => Mar,  3 => Apr,  4 => May,  5 => Jun,
-                 7 => Jul,  8 => Aug,  9 => Sep, 10 => Oct, 11 => Nov, 12 => Dec,
-                14 => D01, 15 => D02, 16 => D03, 17 => D04, 18 => D05, 19 => D06, 20 => D07,
-                21 => D08, 22 => D09, 23 => D10, 24 => D11, 25 => D12, 26 => D13, 27 => D14,
-                28 => D15, 29 => D16, 30 => D17, 31 => D18, 32 => D19, 33 => D20, 34 => D21,
-                35 => D22, 36 => D23, 37 => D24, 38 => D25, 39 => D26, 40 => D27, 41 => D28,
-                42 => D29, 43 => D30, 44 => D31,
-                _ => unreachable!(),
-            })
-        }
+        Square::squares().into_iter().find(|&square| square as u8 == index).ok_or(IndexError)
     }
 }
 
@@ -78,34 +66,84 @@ impl fmt::Display for Square {
             D08 =>   "8", D09 =>   "9", D10 =>  "10", D11 =>  "11", D12 =>  "12", D13 =>  "13", D14 =>  "14",
             D15 =>  "15", D16 =>  "16", D17 =>  "17", D18 =>  "18", D19 =>  "19", D20 =>  "20", D21 =>  "21",
             D22 =>  "22", D23 =>  "23", D24 =>  "24", D25 =>  "25", D26 =>  "26", D27 =>  "27", D28 =>  "28",
-            D29 =>  "29", D30 =>  "30", D31 =>  "31",
+            D29 =>  "29", D30 =>  "30", D31 =>  "31", Sun => "Sun", Mon => "Mon", Tue => "Tue", Wed => "Wed",
+                                                      Thu => "Thu", Fri => "Fri", Sat => "Sat",
         })
     }
 }
 
+#[derive(Debug)]
+pub struct ParseSquareError;
+
+impl FromStr for Square {
+    type Err = ParseSquareError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Square::squares().into_iter()
+            .find(|square| square.to_string().eq_ignore_ascii_case(s.trim()))
+            .ok_or(ParseSquareError)
+    }
+}
+
+const WEEKDAYS: [Square; 7] = [Sun, Mon, Tue, Wed, Thu, Fri, Sat];
+
+/// Squares left uncovered by a solution: a month, a day and, on frames with weekdays, a weekday.
 #[derive(Copy, Clone, Eq, Ord, PartialEq, PartialOrd, Debug, Hash)]
 pub struct Date {
     pub month: Square,
     pub day: Square,
+    pub weekday: Option<Square>,
 }
 
 impl Date {
+    /// Squares of the date: its month, day and weekday, if it has one.
+    pub fn squares(&self) -> Vec<Square> {
+        [Some(self.month), Some(self.day), self.weekday].into_iter().flatten().collect()
+    }
+
+    /// The valid date made up of exactly `squares`, with a weekday if `weekdays` is set.
+    pub fn from_squares(squares: &[Square], weekdays: bool) -> Option<Date> {
+        let only = |kind: fn(&Square) -> bool| match squares.iter().filter(|square| kind(square)).collect::<Vec<_>>()[..] {
+            [&square] => Some(square),
+            _ => None,
+        };
+
+        let date = Date {
+            month: only(Square::is_month)?,
+            day: only(Square::is_day)?,
+            weekday: if weekdays { Some(only(Square::is_weekday)?) } else { None },
+        };
+
+        (squares.len() == date.squares().len() && date.is_valid()).then_some(date)
+    }
+
     pub fn is_valid(&self) -> bool {
+        if self.weekday.is_some_and(|weekday| !weekday.is_weekday()) {
+            return false;
+        }
         match (self.month, self.day) {
-            _ if self.month > Dec || self.day < D01 => false,
+            _ if !self.month.is_month() || !self.day.is_day() => false,
             (Apr | Jun | Sep | Nov, D31) => false,
             (Feb, D30 | D31) => false,
             _ => true
         }
     }
 
+    /// The following date, or the same date on the following weekday if it has one,
+    /// so that every combination of date and weekday is visited.
     pub fn next(&self) -> Self {
         if !self.is_valid() {
-            return Date { month: Jan, day: D01 };
+            return Date { month: Jan, day: D01, weekday: self.weekday.map(|_| Sun) };
+        }
+
+        if let Some(weekday) = self.weekday.filter(|&weekday| weekday != Sat) {
+            let i = WEEKDAYS.iter().position(|&other| other == weekday).unwrap();
+            return Date { weekday: Some(WEEKDAYS[i + 1]), ..*self };
         }
+        let weekday = self.weekday.map(|_| Sun);
 
         if let Ok(next_day) = Square::try_from(self.day as u8 + 1) {
-            let next_date = Date { month: self.month, day: next_day };
+            let next_date = Date { month: self.month, day: next_day, weekday };
             if next_date.is_valid() {
                 return next_date;
             }
@@ -116,13 +154,20 @@ impl Date {
             _ => Square::try_from(self.month as u8 + 1).unwrap(),
         };
 
-        Date { month: next_month, day: D01 }
+        Date { month: next_month, day: D01, weekday }
     }
 
+    /// The date before, in the order of [`Date::next`].
     pub fn prev(&self) -> Self {
         if !self.is_valid() {
-            return Date { month: Jan, day: D01 };
+            return Date { month: Jan, day: D01, weekday: self.weekday.map(|_| Sun) };
+        }
+
+        if let Some(weekday) = self.weekday.filter(|&weekday| weekday != Sun) {
+            let i = WEEKDAYS.iter().position(|&other| other == weekday).unwrap();
+            return Date { weekday: Some(WEEKDAYS[i - 1]), ..*self };
         }
+        let weekday = self.weekday.map(|_| Sat);
 
         if self.day == D01 {
             let prev_month = match self.month {
@@ -134,15 +179,18 @@ impl Date {
                 May | Jul | Oct | Dec => D30,
                 _ => D31,
             };
-            return Date { month: prev_month, day: prev_day };
+            return Date { month: prev_month, day: prev_day, weekday };
         }
 
-        Date { month: self.month, day: Square::try_from(self.day as u8 - 1).unwrap() }
+        Date { month: self.month, day: Square::try_from(self.day as u8 - 1).unwrap(), weekday }
     }
 }
 
 impl fmt::Display for Date {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(weekday) = self.weekday {
+            write!(f, "{weekday} ")?;
+        }
         write!(f, "{} {}", self.month, self.day)
     }
 }
@@ -1,11 +1,18 @@
 pub mod solution_pane;
 pub mod date_pane;
+pub mod filter;
+pub mod play;
+pub mod theme;
+pub mod thumbnail;
 
 use self::solution_pane::SolutionPane;
 use self::date_pane::DatePane;
+use self::theme::Theme;
+use self::filter::{Filter, Prompt};
 
-use crate::board::Board;
+use crate::board::{self, Board};
 use crate::board::square::{Date, DateMap};
+use crate::solutions::{DataError, SolutionIndex};
 
 use std::io;
 use std::cmp::max;
@@ -15,14 +22,14 @@ use rand::Rng;
 
 use crossterm::ExecutableCommand;
 use crossterm::terminal;
-use crossterm::event::{self, Event, KeyCode};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
 
 use ratatui::backend::CrosstermBackend;
 use ratatui::terminal::{Frame, Terminal};
 use ratatui::layout::{Layout, Flex, Position, Size};
 use ratatui::widgets::{Block, Paragraph};
-use ratatui::text::Line;
-use ratatui::style::{Style, Color};
+use ratatui::text::{Line, Text};
+use ratatui::style::Style;
 use ratatui::symbols::border;
 
 enum Message {
@@ -37,79 +44,182 @@ pub enum Pane {
     Solution,
 }
 
+/// Settings for how the TUI is drawn.
+#[derive(Default, Debug)]
+pub struct Options {
+    /// Draw with ASCII characters only.
+    pub ascii: bool,
+    /// Colors to draw with.
+    pub theme: Theme,
+}
+
 #[derive(Debug)]
 pub struct State {
-    solutions: DateMap<Vec<Board>>,
+    options: Options,
+    solutions: SolutionIndex,
+    boards: DateMap<Vec<Board>>,
     solution_count: DateMap<usize>,
     selected_solutions: DateMap<usize>,
     focused_pane: Pane,
     date_pane: DatePane,
     solution_pane: SolutionPane,
+    filter: Filter,
+    prompt: Option<Prompt>,
+    /// Error met loading a date's solutions, ending the browser once it is drawn
+    error: Option<DataError>,
 }
 
 impl State {
-    fn init(boards: DateMap<Vec<Board>>, date: Date) -> Self {
-        let solution_count = boards.iter()
-            .map(|(&date, sols)| (date, sols.len()))
-            .collect();
+    fn init(solutions: SolutionIndex, date: Date, options: Options) -> Self {
+        let solution_count = solutions.dates()
+            .map(|date| (date, solutions.count(&date)))
+            .collect::<DateMap<_>>();
 
         let mut rng = rand::thread_rng();
-        let selected_solutions = boards.iter()
-            .map(|(&date, sols)| (date, rng.gen_range(0..max(1, sols.len()))))
+        let selected_solutions = solution_count.iter()
+            .map(|(&date, &count)| (date, rng.gen_range(0..max(1, count))))
             .collect();
 
         State {
-            solutions: boards,
+            options,
+            solutions,
+            boards: DateMap::new(),
             solution_count,
             selected_solutions,
             focused_pane: Pane::Solution,
             date_pane: DatePane::new(date),
             solution_pane: SolutionPane::default(),
+            filter: Filter::default(),
+            prompt: None,
+            error: None,
         }
     }
 }
 
-pub const BIG: Size = Size { width: 33, height: 17 };
-pub const SMALL: Size = Size { width: 15, height: 8 };
+impl State {
+    /// Solutions for `date` which match the filter, updating its count and selection once they are loaded.
+    ///
+    /// If they cannot be decoded, none are shown and the error is kept to be reported by [`browse`].
+    fn boards(&mut self, date: Date) -> &Vec<Board> {
+        if !self.boards.contains_key(&date) {
+            let mut boards = self.solutions.get(&date).unwrap_or_else(|err| {
+                self.error.get_or_insert(err);
+                Vec::new()
+            });
+            boards.retain(|board| self.filter.matches(board));
+
+            self.solution_count.insert(date, boards.len());
+            if let Some(index) = self.selected_solutions.get_mut(&date) {
+                *index = (*index).min(boards.len().saturating_sub(1));
+            }
+            self.boards.insert(date, boards);
+        }
+        &self.boards[&date]
+    }
+
+    /// Shows only solutions matching `filter`, keeping the selected solution if it still matches.
+    fn set_filter(&mut self, filter: Filter) {
+        let date = self.date_pane.selected;
+        let selected = self.selected_solutions.get(&date)
+            .and_then(|&index| self.boards.get(&date)?.get(index).cloned());
+
+        self.filter = filter;
+        self.boards.clear();
+
+        let index = selected.and_then(|selected| self.boards(date).iter().position(|board| *board == selected));
+        if let Some(index) = index {
+            self.selected_solutions.insert(date, index);
+        }
+        solution_pane::center_selection(self);
+    }
+
+    fn big(&self) -> Size {
+        big(self.solutions.frame())
+    }
+
+    fn small(&self) -> Size {
+        small(self.solutions.frame())
+    }
+
+    fn board_text(&self, board: &Board) -> Text<'static> {
+        let drawing = if self.options.ascii { board.to_ascii_string() } else { board.to_string() };
+        thumbnail::big(board, board.solved_for(), drawing, &self.options.theme)
+    }
+
+    fn mini_board_text(&self, board: &Board) -> Text<'static> {
+        let drawing = if self.options.ascii { board.to_mini_ascii_string() } else { board.to_mini_string() };
+        thumbnail::small(board, board.solved_for(), drawing, &self.options.theme)
+    }
+}
+
+const ASCII_BORDER: border::Set = border::Set {
+    top_left: "+",
+    top_right: "+",
+    bottom_left: "+",
+    bottom_right: "+",
+    vertical_left: "|",
+    vertical_right: "|",
+    horizontal_top: "-",
+    horizontal_bottom: "-",
+};
+
+/// Size of a board of `frame` as written by `Display` for [`Board`].
+pub fn big(frame: board::Frame) -> Size {
+    Size { width: 4 * frame.cols() as u16 + 5, height: 2 * frame.rows() as u16 + 3 }
+}
+
+/// Size of a board of `frame` as written by [`Board::to_mini_string`].
+pub fn small(frame: board::Frame) -> Size {
+    Size { width: 2 * frame.cols() as u16 + 1, height: frame.rows() as u16 + 1 }
+}
+
 pub const PADDING: u16 = 3;
 
 fn draw(state: &mut State, frame: &mut Frame) {
     state.solution_pane.num_cols = {
-        let max_solution_pane_width = frame.size().width - 4*(PADDING + 1) - BIG.width;
-        max_solution_pane_width / (SMALL.width + 2)
+        let max_solution_pane_width = frame.size().width - 4*(PADDING + 1) - state.big().width;
+        max_solution_pane_width / (state.small().width + 2)
     };
 
-    if state.solution_pane.num_cols == 0 || frame.size().height < BIG.height + 2 {
+    if state.solution_pane.num_cols == 0 || frame.size().height < state.big().height + 2 {
         frame.render_widget(Paragraph::new("Terminal size is too small"), frame.size());
         return;
     }
 
     let panes = Layout::horizontal([
-        BIG.width + 2*(PADDING + 1),
-        state.solution_pane.num_cols * (SMALL.width + 2) + 2*PADDING
+        state.big().width + 2*(PADDING + 1),
+        state.solution_pane.num_cols * (state.small().width + 2) + 2*PADDING
     ])
         .flex(Flex::Center)
         .split(frame.size());
 
     let block = Block::bordered()
-        .border_set(border::Set {
-            bottom_left: border::QUADRANT_RIGHT_HALF,
-            bottom_right: border::QUADRANT_LEFT_HALF,
-            horizontal_bottom: border::QUADRANT_BLOCK,
-            ..border::QUADRANT_INSIDE
+        .border_set(if state.options.ascii { ASCII_BORDER } else {
+            border::Set {
+                bottom_left: border::QUADRANT_RIGHT_HALF,
+                bottom_right: border::QUADRANT_LEFT_HALF,
+                horizontal_bottom: border::QUADRANT_BLOCK,
+                ..border::QUADRANT_INSIDE
+            }
         })
-        .title_style(Style::default().fg(Color::Black).bg(Color::Blue));
+        .title_style(Style::default().fg(state.options.theme.text).bg(state.options.theme.focused));
+
+    let (switch, quit, up_down, arrows, filter) = if state.options.ascii {
+        ("| Tab: Switch | ", "| q: Quit |", "Up/Down: Choose | ", "Arrows: Choose | ", "/: Filter | ")
+    } else {
+        ("▎󰌒 : Switch▕▏", "▕▏󱊷 : Quit🮇", "↑/↓ : Choose▕▏", "↑/↓/←/→ : Choose▕▏", "/ : Filter▕▏")
+    };
 
     // Render date pane
 
     frame.render_widget(
         match state.focused_pane {
             Pane::Date => block.clone()
-                .border_style(Style::default().fg(Color::Blue))
-                .title_bottom(Line::from("▎󰌒 : Switch▕▏↑/↓ : Choose▕▏").left_aligned())
-                .title_bottom(Line::from("▕▏󱊷 : Quit🮇").right_aligned()),
+                .border_style(Style::default().fg(state.options.theme.focused))
+                .title_bottom(Line::from(format!("{switch}{up_down}")).left_aligned())
+                .title_bottom(Line::from(quit).right_aligned()),
             Pane::Solution => block.clone()
-                .border_style(Style::default().fg(Color::DarkGray)),
+                .border_style(Style::default().fg(state.options.theme.unfocused)),
         },
         panes[0]
     );
@@ -118,18 +228,24 @@ fn draw(state: &mut State, frame: &mut Frame) {
     date_pane::draw(state, frame);
 
     // Render solution pane
-    
-    frame.render_widget(
-        match state.focused_pane {
-            Pane::Date => block.clone()
-                .border_style(Style::default().fg(Color::DarkGray)),
-            Pane::Solution => block.clone()
-                .border_style(Style::default().fg(Color::Blue))
-                .title_bottom(Line::from("▎󰌒 : Switch▕▏↑/↓/←/→ : Choose▕▏").left_aligned())
-                .title_bottom(Line::from("▕▏󱊷 : Quit🮇").right_aligned()),
-        },
-        panes[1]
-    );
+
+    let mut solution_block = match state.focused_pane {
+        Pane::Date => block.clone()
+            .border_style(Style::default().fg(state.options.theme.unfocused)),
+        Pane::Solution => block.clone()
+            .border_style(Style::default().fg(state.options.theme.focused))
+            .title_bottom(Line::from(format!("{switch}{arrows}{filter}")).left_aligned())
+            .title_bottom(Line::from(quit).right_aligned()),
+    };
+    if let Some(prompt) = &state.prompt {
+        solution_block = solution_block.title_top(Line::from(format!(" Filter: {}_ ", prompt.input)).left_aligned());
+        if let Some(error) = &prompt.error {
+            solution_block = solution_block.title_top(Line::from(format!(" {error} ")).right_aligned());
+        }
+    } else if !state.filter.is_empty() {
+        solution_block = solution_block.title_top(Line::from(format!(" Filter: {} ", state.filter)).left_aligned());
+    }
+    frame.render_widget(solution_block, panes[1]);
 
     state.solution_pane.area = block.inner(panes[1]);
     solution_pane::draw(state, frame);
@@ -138,10 +254,17 @@ fn draw(state: &mut State, frame: &mut Frame) {
 fn update(state: &mut State) -> io::Result<Message> {
     if event::poll(Duration::from_millis(100))? {
         match event::read()? {
+            Event::Key(key) if state.prompt.is_some() => {
+                filter::update(state, key);
+            }
             Event::Key(key) => {
                 if matches!(key.code, KeyCode::Esc | KeyCode::Char('q')) {
                     return Ok(Message::Quit)
                 }
+                if key.code == KeyCode::Char('/') && key.kind == KeyEventKind::Press {
+                    filter::open(state);
+                    return Ok(Message::Continue)
+                }
 
                 match state.focused_pane {
                     Pane::Date     => { date_pane::update(state, &Event::Key(key)); }
@@ -166,11 +289,11 @@ fn update(state: &mut State) -> io::Result<Message> {
     Ok(Message::Continue)
 }
 
-fn run(boards: DateMap<Vec<Board>>, date: Date) -> io::Result<()> {
+fn run(solutions: SolutionIndex, date: Date, options: Options) -> io::Result<()> {
     let mut terminal = Terminal::new(CrosstermBackend::new(io::stdout()))?;
     terminal.clear()?;
 
-    let mut state = State::init(boards, date);
+    let mut state = State::init(solutions, date, options);
 
     terminal.draw(|frame| draw(&mut state, frame))?;
     date_pane::center_selection(&mut state);
@@ -178,6 +301,9 @@ fn run(boards: DateMap<Vec<Board>>, date: Date) -> io::Result<()> {
 
     loop {
         terminal.draw(|frame| draw(&mut state, frame))?;
+        if let Some(err) = state.error.take() {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, format!("could not decode solutions: {err}")));
+        }
         match update(&mut state)? {
             Message::Quit => { break; }
             Message::Continue => {}
@@ -206,9 +332,9 @@ fn shutdown() -> io::Result<()> {
     terminal::disable_raw_mode()
 }
 
-pub fn browse(boards: DateMap<Vec<Board>>, date: Date) -> io::Result<()> {
+pub fn browse(solutions: SolutionIndex, date: Date, options: Options) -> io::Result<()> {
     startup()?;
-    let result = run(boards, date);
+    let result = run(solutions, date, options);
     shutdown()?;
     result
 }
@@ -1,4 +1,4 @@
-use super::{solution_pane, Pane, State, BIG, PADDING};
+use super::{solution_pane, Pane, State, PADDING};
 
 use crate::board::{Board, DateMap};
 use crate::board::square::Date;
@@ -11,7 +11,7 @@ use ratatui::terminal::Frame;
 use ratatui::layout::{Rect, Position, Size, Offset};
 use ratatui::widgets::{Paragraph, Block, Padding};
 use ratatui::text::Line;
-use ratatui::style::{Style, Color, Modifier};
+use ratatui::style::{Style, Modifier};
 
 #[derive(Debug)]
 pub struct DatePane {
@@ -37,16 +37,18 @@ impl DatePane {
 pub fn draw(state: &mut State, frame: &mut Frame) {
     state.date_pane.buttons.clear();
 
-    let color = match state.focused_pane {
-        Pane::Date     => Color::Blue,
-        Pane::Solution => Color::DarkGray,
+    let theme = &state.options.theme;
+    let (color, selection) = match state.focused_pane {
+        Pane::Date     => (theme.focused, theme.selection),
+        Pane::Solution => (theme.unfocused, theme.unfocused),
     };
+    let text = theme.text;
     let origin = Position::from(state.date_pane.area);
     let mut date = state.date_pane.top_date;
     for i in 0.. {
-        let offset = Offset { x: PADDING as i32 - 1, y: (BIG.height as i32 + 1) * i - state.date_pane.scroll };
+        let offset = Offset { x: PADDING as i32 - 1, y: (state.big().height as i32 + 1) * i - state.date_pane.scroll };
 
-        let mut rect = Rect::from((origin, Size { width: BIG.width + 2, ..BIG }))
+        let mut rect = Rect::from((origin, Size { width: state.big().width + 2, ..state.big() }))
             .offset(offset)
             .intersection(state.date_pane.area);
 
@@ -61,30 +63,32 @@ pub fn draw(state: &mut State, frame: &mut Frame) {
             break;
         }
 
+        let boards = state.boards(date).clone();
         let index = *state.selected_solutions.get(&date).unwrap_or(&usize::MAX);
-        let (empty_vec, empty_board) = (Vec::default(), Board::default());
-        let board = state.solutions
-            .get(&date)
-            .unwrap_or(&empty_vec)
-            .get(index)
-            .unwrap_or(&empty_board);
-
-        let thumbnail = Paragraph::new(board.to_string())
-            .style(Style::default().fg(color))
+        let board = boards.get(index).cloned().unwrap_or_else(|| Board::new(state.solutions.frame()));
+
+        let style = if date == state.date_pane.selected {
+            Style::default().fg(text).bg(selection)
+        } else {
+            Style::default().fg(color)
+        };
+        let thumbnail = Paragraph::new(state.board_text(&board))
+            .style(style)
             .block(Block::new().padding(Padding::horizontal(1)))
-            .scroll(if date == state.date_pane.top_date { (BIG.height - rect.height, 0) } else { (0, 0) });
+            .scroll(if date == state.date_pane.top_date { (state.big().height - rect.height, 0) } else { (0, 0) });
 
         frame.render_widget(thumbnail, rect);
 
         if let Some(&index) = state.selected_solutions.get(&date) {
             if let Some(&count) = state.solution_count.get(&date) {
-                let info = Line::from(format!("#{} / {}", index + 1, count))
+                let info = if count == 0 { "no matches".to_string() } else { format!("#{} / {}", index + 1, count) };
+                let info = Line::from(info)
                     .style(Style::default().add_modifier(Modifier::ITALIC))
                     .right_aligned();
 
                 let info_rect = state.date_pane.area.intersection(Rect {
-                    x: rect.left() + 17,
-                    y: if date == state.date_pane.top_date { rect.bottom() - 2 } else { rect.top() + 15 },
+                    x: rect.left() + state.big().width - 16,
+                    y: if date == state.date_pane.top_date { rect.bottom() - 2 } else { rect.top() + state.big().height - 2 },
                     width: 15,
                     height: 1,
                 });
@@ -99,11 +103,6 @@ pub fn draw(state: &mut State, frame: &mut Frame) {
 
         date = date.next();
     }
-
-    if let Some((_, &rect)) = state.date_pane.buttons.iter().find(|&(&date, _)| date == state.date_pane.selected) {
-        let block = Block::new().style(Style::default().fg(Color::Black).bg(color));
-        frame.render_widget(block, rect);
-    }
 }
 
 pub fn update(state: &mut State, event: &Event) {
@@ -150,22 +149,22 @@ pub fn update(state: &mut State, event: &Event) {
 
 pub fn center_selection(state: &mut State) {
     state.date_pane.top_date = state.date_pane.selected;
-    let center_y = state.date_pane.area.top() + state.date_pane.area.height / 2 - (BIG.height + 1) / 2;
+    let center_y = state.date_pane.area.top() + state.date_pane.area.height / 2 - state.big().height.div_ceil(2);
     state.date_pane.scroll = -(center_y as i32);
     fix_scroll(state);
 }
 
 pub fn scroll_to_selection(state: &mut State) {
-    if state.date_pane.area.height < 3 * (BIG.height + 1) {
+    if state.date_pane.area.height < 3 * (state.big().height + 1) {
         center_selection(state);
         return;
     }
 
-    let bottom = (state.date_pane.area.bottom() + (BIG.height + 1)) as i32;
+    let bottom = (state.date_pane.area.bottom() + (state.big().height + 1)) as i32;
     let mut dates_on_screen = std::iter::successors(
-        Some((state.date_pane.top_date.prev(), -state.date_pane.scroll - (BIG.height + 1) as i32)),
+        Some((state.date_pane.top_date.prev(), -state.date_pane.scroll - (state.big().height + 1) as i32)),
         |(date, y)| {
-            let next_y = y + (BIG.height + 1) as i32;
+            let next_y = y + (state.big().height + 1) as i32;
             (next_y < bottom).then_some((date.next(), next_y))
         }
     );
@@ -174,7 +173,7 @@ pub fn scroll_to_selection(state: &mut State) {
         None => { center_selection(state); }
         Some((_, selected_y)) => {
             let top_y = (state.date_pane.area.top() + PADDING / 2) as i32 - 1;
-            let bottom_y = (state.date_pane.area.bottom() - BIG.height - PADDING / 2 - 1) as i32;
+            let bottom_y = (state.date_pane.area.bottom() - state.big().height - PADDING / 2 - 1) as i32;
             if top_y > selected_y {
                 state.date_pane.scroll -= top_y - selected_y;
                 fix_scroll(state);
@@ -188,12 +187,12 @@ pub fn scroll_to_selection(state: &mut State) {
 
 fn fix_scroll(state: &mut State) {
     while state.date_pane.scroll < 0 {
-        state.date_pane.scroll += (BIG.height + 1) as i32;
+        state.date_pane.scroll += (state.big().height + 1) as i32;
         state.date_pane.top_date = state.date_pane.top_date.prev();
     }
 
-    while state.date_pane.scroll >= (BIG.height + 1) as i32 {
-        state.date_pane.scroll -= (BIG.height + 1) as i32;
+    while state.date_pane.scroll >= (state.big().height + 1) as i32 {
+        state.date_pane.scroll -= (state.big().height + 1) as i32;
         state.date_pane.top_date = state.date_pane.top_date.next();
     }
 }
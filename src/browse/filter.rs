@@ -0,0 +1,178 @@
+use super::State;
+
+use crate::board::{Board, Path, Piece, Placement, Square, Rotation};
+
+use std::fmt;
+use std::str::FromStr;
+
+use crossterm::event::{KeyEvent, KeyEventKind, KeyCode};
+
+/// A condition on where a piece is placed.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Constraint {
+    /// The piece is placed at `square`, if given, in the given orientation, if any.
+    ///
+    /// Orientations are told apart by the squares they cover, so that for pieces with symmetries
+    /// any of the orientations covering the same squares as the one placed will do.
+    Placed { piece: Piece, square: Option<Square>, orientation: Option<(Rotation, bool)> },
+    /// The piece covers `square`.
+    Covers { piece: Piece, square: Square },
+}
+
+impl Constraint {
+    fn matches(&self, board: &Board) -> bool {
+        match *self {
+            Constraint::Placed { piece, square, orientation } => {
+                let Ok(placements) = board.placements() else { return false; };
+                let Some(placed) = placements.iter().find(|placement| placement.piece == piece) else { return false; };
+                match (square, orientation) {
+                    // A turned piece may cover the same squares from another anchor, so the squares are compared.
+                    (Some(square), Some((rotation, mirror))) => {
+                        let frame = board.frame();
+                        Board::new(frame).apply(&Placement { piece, square, rotation, mirror }).is_some_and(|alone| {
+                            frame.squares().into_iter().all(|square| {
+                                (alone.piece_at(square) == Some(piece)) == (board.piece_at(square) == Some(piece))
+                            })
+                        })
+                    }
+                    (square, orientation) => {
+                        let footprint = |(rotation, mirror)| Path::from_orientation(piece, rotation, mirror).footprint();
+                        square.is_none_or(|square| placed.square == square)
+                            && orientation.is_none_or(|orientation| footprint(orientation) == footprint((placed.rotation, placed.mirror)))
+                    }
+                }
+            }
+            Constraint::Covers { piece, square } => board.piece_at(square) == Some(piece),
+        }
+    }
+}
+
+impl fmt::Display for Constraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Constraint::Placed { piece, square, orientation } => {
+                write!(f, "{}", piece.letter())?;
+                if let Some(square) = square { write!(f, "@{square}")?; }
+                if let Some((rotation, mirror)) = orientation {
+                    write!(f, ":{}", rotation as u8)?;
+                    if mirror { write!(f, "m")?; }
+                }
+                Ok(())
+            }
+            Constraint::Covers { piece, square } => write!(f, "{}~{square}", piece.letter()),
+        }
+    }
+}
+
+impl FromStr for Constraint {
+    type Err = String;
+
+    /// Parses constraints of the form `piece~square`, e.g. `L~Jan`, or `piece[@square][:rotation[m]]`,
+    /// e.g. `O@Jan`, `L:1m` or `L@Jan:1m`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_piece = |piece: &str| piece.parse::<Piece>().map_err(|_| format!("invalid piece `{piece}`"));
+        let parse_square = |square: &str| square.parse::<Square>().map_err(|_| format!("invalid square `{square}`"));
+
+        if let Some((piece, square)) = s.split_once('~') {
+            return Ok(Constraint::Covers { piece: parse_piece(piece)?, square: parse_square(square)? });
+        }
+
+        let (rest, orientation) = match s.split_once(':') {
+            Some((rest, orientation)) => (rest, Some(orientation)),
+            None => (s, None),
+        };
+        let (piece, square) = match rest.split_once('@') {
+            Some((piece, square)) => (piece, Some(parse_square(square)?)),
+            None => (rest, None),
+        };
+        let orientation = match orientation {
+            None => None,
+            Some(orientation) => {
+                let (rotation, mirror) = match orientation.strip_suffix(['m', 'M']) {
+                    Some(rotation) => (rotation, true),
+                    None => (orientation, false),
+                };
+                match rotation.parse::<u8>() {
+                    Ok(amount) if amount < 4 => Some((Rotation::from(amount), mirror)),
+                    _ => return Err(format!("invalid rotation `{rotation}`, must be in range 0-3")),
+                }
+            }
+        };
+
+        Ok(Constraint::Placed { piece: parse_piece(piece)?, square, orientation })
+    }
+}
+
+/// Constraints which every board shown must satisfy.
+#[derive(Clone, Default, PartialEq, Eq, Debug)]
+pub struct Filter(Vec<Constraint>);
+
+impl Filter {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn matches(&self, board: &Board) -> bool {
+        self.0.iter().all(|constraint| constraint.matches(board))
+    }
+}
+
+impl fmt::Display for Filter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let constraints = self.0.iter().map(Constraint::to_string).collect::<Vec<_>>();
+        write!(f, "{}", constraints.join(" "))
+    }
+}
+
+impl FromStr for Filter {
+    type Err = String;
+
+    /// Parses constraints separated by spaces or commas.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split([' ', ','])
+            .filter(|constraint| !constraint.is_empty())
+            .map(str::parse)
+            .collect::<Result<_, _>>()
+            .map(Filter)
+    }
+}
+
+/// Text being typed into the filter prompt.
+#[derive(Default, Debug)]
+pub struct Prompt {
+    pub input: String,
+    pub error: Option<String>,
+}
+
+/// Opens the prompt to edit the current filter.
+pub fn open(state: &mut State) {
+    let input = if state.filter.is_empty() { String::new() } else { format!("{} ", state.filter) };
+    state.prompt = Some(Prompt { input, error: None });
+}
+
+/// Edits the open prompt, applying the filter typed on Enter, or closing it without change on Esc.
+pub fn update(state: &mut State, key: KeyEvent) {
+    let Some(prompt) = state.prompt.as_mut() else { return; };
+    if key.kind != KeyEventKind::Press {
+        return;
+    }
+    match key.code {
+        KeyCode::Esc => { state.prompt = None; }
+        KeyCode::Enter => match prompt.input.parse::<Filter>() {
+            Ok(filter) => {
+                state.prompt = None;
+                state.set_filter(filter);
+            }
+            Err(error) => { prompt.error = Some(error); }
+        },
+        KeyCode::Backspace => {
+            prompt.input.pop();
+            prompt.error = None;
+        }
+        KeyCode::Char(c) => {
+            prompt.input.push(c);
+            prompt.error = None;
+        }
+        _ => {}
+    }
+}
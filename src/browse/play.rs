@@ -0,0 +1,328 @@
+use super::{thumbnail, startup, shutdown, Message, Options, ASCII_BORDER, PADDING, big};
+
+use crate::board::{Board, Date, DeadEnd, Path, Piece, Rotation, Square};
+use crate::solutions;
+
+use std::io;
+use std::time::Duration;
+use std::sync::mpsc::{Receiver, TryRecvError};
+
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+
+use ratatui::backend::CrosstermBackend;
+use ratatui::terminal::{Frame, Terminal};
+use ratatui::layout::{Layout, Constraint, Flex, Rect};
+use ratatui::widgets::{Block, Paragraph, Padding, Wrap};
+use ratatui::text::{Line, Span};
+use ratatui::style::{Style, Modifier};
+use ratatui::symbols::border;
+
+/// Width of the column listing pieces and keys.
+const SIDE_WIDTH: u16 = 24;
+/// Pieces listed on each line of the side column.
+const PIECES_PER_LINE: usize = 8;
+
+#[derive(Debug)]
+struct State {
+    options: Options,
+    date: Date,
+    board: Board,
+    /// Boards before each piece was dropped, to undo to.
+    history: Vec<Board>,
+    piece: Piece,
+    rotation: Rotation,
+    mirror: bool,
+    /// Row and column of the first square of the piece, which may be off the frame.
+    cursor: (i32, i32),
+    message: Option<String>,
+    /// Solutions extending the board, searched for in the background after a piece is dropped.
+    search: Option<Receiver<Board>>,
+}
+
+impl State {
+    fn new(board: Board, date: Date, options: Options) -> Self {
+        State {
+            options,
+            date,
+            piece: board.frame().pieces()[0],
+            board,
+            history: Vec::new(),
+            rotation: Rotation::Zero,
+            mirror: false,
+            cursor: (2, 3),
+            message: None,
+            search: None,
+        }
+    }
+
+    fn path(&self) -> Path {
+        Path::from_orientation(self.piece, self.rotation, self.mirror)
+    }
+
+    /// Rows and columns of the squares the piece would cover if dropped.
+    fn cells(&self) -> Vec<(i32, i32)> {
+        let (row, col) = self.cursor;
+        self.path().cells().into_iter().map(|cell| (row + cell.0, col + cell.1)).collect()
+    }
+
+    /// The board with the piece dropped, or why it cannot be.
+    fn dropped(&self) -> Result<Board, String> {
+        let piece = self.piece;
+        let squares = self.cells().into_iter().map(|(row, col)| self.square(row, col)).collect::<Option<Vec<_>>>()
+            .ok_or_else(|| format!("{piece} must be placed within the frame"))?;
+
+        if let Some(square) = squares.iter().find(|&square| self.date.squares().contains(square)) {
+            return Err(format!("{piece} would cover {square}, which must be left open"));
+        }
+
+        self.board.place(piece, squares[0], &self.path())
+            .ok_or_else(|| format!("{piece} overlaps another piece"))
+    }
+
+    /// Square at (`row`, `col`), if there is one in the frame.
+    fn square(&self, row: i32, col: i32) -> Option<Square> {
+        self.board.frame().square_at(row as isize, col as isize)
+    }
+
+    fn remaining(&self) -> Vec<Piece> {
+        self.board.frame().pieces().into_iter().filter(|&piece| !self.board.contains(piece)).collect()
+    }
+
+    /// Selects the next piece yet to be placed after the current one, wrapping around.
+    fn next_piece(&mut self) {
+        let remaining = self.remaining();
+        if let Some(&piece) = remaining.iter().find(|&&piece| piece > self.piece).or(remaining.first()) {
+            self.piece = piece;
+        }
+    }
+
+    fn select(&mut self, piece: Piece) {
+        if !self.board.frame().pieces().contains(&piece) {
+            self.message = Some(format!("{piece} is not played with this frame"));
+        } else if self.board.contains(piece) {
+            self.message = Some(format!("{piece} is already placed"));
+        } else {
+            self.piece = piece;
+            self.message = None;
+        }
+    }
+
+    fn drop_piece(&mut self) {
+        if self.board.contains(self.piece) {
+            return;
+        }
+
+        match self.dropped() {
+            Err(message) => { self.message = Some(message); }
+            Ok(board) => {
+                self.history.push(self.board.clone());
+                self.board = board;
+                self.message = None;
+                self.search = None;
+                self.next_piece();
+
+                if self.remaining().is_empty() {
+                    self.message = Some(match self.board.solved_for() {
+                        Some(date) if date == self.date => format!("Solved {date}, congratulations!"),
+                        _ => "Every piece is placed, but the date is not left open".to_string(),
+                    });
+                } else if let Some(dead_end) = self.board.dead_end(self.date) {
+                    self.message = Some(format!("Dead end: {dead_end}"));
+                } else {
+                    self.search = Some(solutions::solve_from(self.board.clone(), self.date));
+                }
+            }
+        }
+    }
+
+    fn undo(&mut self) {
+        if let Some(board) = self.history.pop() {
+            if let Some(piece) = self.board.frame().pieces().into_iter().find(|&piece| self.board.contains(piece) && !board.contains(piece)) {
+                self.piece = piece;
+            }
+            self.board = board;
+            self.message = None;
+            self.search = None;
+        }
+    }
+
+    /// Reports a dead end once the search started by the last drop ends without finding a solution.
+    fn check_search(&mut self) {
+        let Some(search) = &self.search else { return; };
+        match search.try_recv() {
+            Ok(_) => { self.search = None; }
+            Err(TryRecvError::Empty) => {}
+            Err(TryRecvError::Disconnected) => {
+                self.search = None;
+                self.message = Some(format!("Dead end: {}", DeadEnd::NoSolution));
+            }
+        }
+    }
+}
+
+fn draw(state: &State, frame: &mut Frame) {
+    let big = big(state.board.frame());
+    let width = big.width + 2*(PADDING + 1) + SIDE_WIDTH;
+    let height = big.height + 6;
+    if frame.size().width < width || frame.size().height < height {
+        frame.render_widget(Paragraph::new("Terminal size is too small"), frame.size());
+        return;
+    }
+
+    let [area] = Layout::horizontal([width]).flex(Flex::Center).areas(frame.size());
+    let [area] = Layout::vertical([height]).flex(Flex::Center).areas(area);
+
+    let theme = &state.options.theme;
+    let quit = if state.options.ascii { "| q: Quit |" } else { "▕▏󱊷 : Quit🮇" };
+    let block = Block::bordered()
+        .border_set(if state.options.ascii { ASCII_BORDER } else {
+            border::Set {
+                bottom_left: border::QUADRANT_RIGHT_HALF,
+                bottom_right: border::QUADRANT_LEFT_HALF,
+                horizontal_bottom: border::QUADRANT_BLOCK,
+                ..border::QUADRANT_INSIDE
+            }
+        })
+        .border_style(Style::default().fg(theme.focused))
+        .title_style(Style::default().fg(theme.text).bg(theme.focused))
+        .title_top(Line::from(format!(" {} ", state.date)).centered())
+        .title_bottom(Line::from(quit).right_aligned());
+    frame.render_widget(block.clone(), area);
+
+    let [board_area, side_area] = Layout::horizontal([big.width + 2*PADDING, SIDE_WIDTH]).areas(block.inner(area));
+    let [board_area, message_area] = Layout::vertical([Constraint::Length(big.height + 1), Constraint::Length(3)]).areas(board_area);
+
+    // Board, with the piece to be dropped drawn over it
+
+    let drawing = if state.options.ascii { state.board.to_ascii_string() } else { state.board.to_string() };
+    let board = Paragraph::new(thumbnail::big(&state.board, Some(state.date), drawing, theme))
+        .style(Style::default().fg(theme.focused))
+        .block(Block::new().padding(Padding::new(PADDING - 1, 0, 1, 0)));
+    frame.render_widget(board, board_area);
+
+    let fits = state.dropped().is_ok();
+    let color = theme.pieces[state.board.frame().color_index(state.piece)];
+    for (row, col) in state.cells() {
+        if state.square(row, col).is_none() || state.board.contains(state.piece) {
+            continue;
+        }
+        let cell = Rect {
+            x: board_area.x + PADDING - 1 + 3 + 4 * col as u16,
+            y: board_area.y + 1 + 2 + 2 * row as u16,
+            width: 3,
+            height: 1,
+        };
+        let span = match (fits, state.options.ascii) {
+            (true, _) => Span::styled("   ", Style::default().bg(color)),
+            (false, false) => Span::styled("╳╳╳", Style::default().fg(color)),
+            (false, true) => Span::styled("xxx", Style::default().fg(color)),
+        };
+        frame.render_widget(span, cell);
+    }
+
+    if let Some(message) = &state.message {
+        let message = Paragraph::new(message.as_str())
+            .style(Style::default().add_modifier(Modifier::ITALIC))
+            .centered()
+            .wrap(Wrap { trim: true });
+        frame.render_widget(message, message_area);
+    }
+
+    // Pieces and keys
+
+    let mut pieces = Vec::new();
+    for piece in state.board.frame().pieces() {
+        let style = if state.board.contains(piece) {
+            Style::default().fg(theme.unfocused)
+        } else if piece == state.piece {
+            Style::default().fg(theme.text).bg(theme.selection)
+        } else {
+            Style::default().fg(theme.pieces[state.board.frame().color_index(piece)])
+        };
+        pieces.push(Span::styled(format!(" {} ", piece.letter()), style));
+    }
+
+    let arrows = if state.options.ascii { "Arrows" } else { "↑/↓/←/→" };
+    let letters = state.board.frame().pieces().iter().map(Piece::letter).collect::<Vec<_>>();
+    let letters = format!("{}-{}", letters.iter().min().unwrap_or(&'L'), letters.iter().max().unwrap_or(&'Z'));
+    let keys = [
+        (arrows, "Move"),
+        ("r/e", "Rotate"),
+        ("m", "Mirror"),
+        ("Tab", "Next piece"),
+        (letters.as_str(), "Choose piece"),
+        ("Space", "Drop"),
+        ("u", "Undo"),
+    ];
+
+    let mut lines = vec![Line::from(" Pieces")];
+    for row in pieces.chunks(PIECES_PER_LINE) {
+        lines.push(Line::from([&[Span::raw(" ")], row].concat()));
+    }
+    lines.push(Line::from(""));
+    lines.extend(keys.iter().map(|(key, action)| Line::from(format!(" {key:<8}{action}"))));
+    frame.render_widget(Paragraph::new(lines).block(Block::new().padding(Padding::top(1))), side_area);
+}
+
+fn update(state: &mut State) -> io::Result<Message> {
+    if event::poll(Duration::from_millis(100))? {
+        match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => {
+                let (row, col) = state.cursor;
+                let frame = state.board.frame();
+                let (last_row, last_col) = (frame.rows() as i32 + 3, frame.cols() as i32 + 3);
+                match key.code {
+                    KeyCode::Esc | KeyCode::Char('q') => { return Ok(Message::Quit); }
+                    KeyCode::Left  | KeyCode::Char('h') => { state.cursor = (row, (col - 1).max(-4)); }
+                    KeyCode::Right | KeyCode::Char('l') => { state.cursor = (row, (col + 1).min(last_col)); }
+                    KeyCode::Up    | KeyCode::Char('k') => { state.cursor = ((row - 1).max(-4), col); }
+                    KeyCode::Down  | KeyCode::Char('j') => { state.cursor = ((row + 1).min(last_row), col); }
+                    KeyCode::Char('r') => { state.rotation = Rotation::from(state.rotation as u8 + 1); }
+                    KeyCode::Char('e') => { state.rotation = Rotation::from(state.rotation as u8 + 3); }
+                    KeyCode::Char('m') => { state.mirror = !state.mirror; }
+                    KeyCode::Tab => { state.next_piece(); }
+                    KeyCode::Char(' ') | KeyCode::Enter => { state.drop_piece(); }
+                    KeyCode::Char('u') | KeyCode::Backspace => { state.undo(); }
+                    KeyCode::Char(c) if c.is_ascii_uppercase() => {
+                        if let Ok(piece) = c.to_string().parse::<Piece>() {
+                            state.select(piece);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Event::Resize(_, _) => {
+                return Ok(Message::Redraw);
+            }
+            _ => {}
+        }
+    }
+    Ok(Message::Continue)
+}
+
+fn run(board: Board, date: Date, options: Options) -> io::Result<()> {
+    let mut terminal = Terminal::new(CrosstermBackend::new(io::stdout()))?;
+    terminal.clear()?;
+
+    let mut state = State::new(board, date, options);
+
+    loop {
+        state.check_search();
+        terminal.draw(|frame| draw(&state, frame))?;
+        match update(&mut state)? {
+            Message::Quit => { break; }
+            Message::Continue | Message::Redraw => {}
+        };
+    }
+
+    Ok(())
+}
+
+/// Opens a TUI in which to solve the puzzle for `date` by placing pieces one at a time on `board`,
+/// which is empty but for its frame.
+pub fn play(board: Board, date: Date, options: Options) -> io::Result<()> {
+    startup()?;
+    let result = run(board, date, options);
+    shutdown()?;
+    result
+}
@@ -1,4 +1,4 @@
-use super::{State, Pane, SMALL, PADDING};
+use super::{State, Pane, PADDING};
 
 use std::cmp::max;
 use std::collections::HashMap;
@@ -8,7 +8,7 @@ use crossterm::event::{Event, KeyEventKind, KeyCode, MouseEventKind, MouseButton
 use ratatui::terminal::Frame;
 use ratatui::layout::{Rect, Position, Size, Offset};
 use ratatui::widgets::{Paragraph, Block, Padding};
-use ratatui::style::{Style, Color};
+use ratatui::style::Style;
 
 #[derive(Default, Debug)]
 pub struct SolutionPane {
@@ -21,24 +21,28 @@ pub struct SolutionPane {
 pub fn draw(state: &mut State, frame: &mut Frame) {
     state.solution_pane.buttons.clear();
 
-    if let Some(boards) = state.solutions.get(&state.date_pane.selected) {
-        let color = match state.focused_pane {
-            Pane::Date     => Color::DarkGray,
-            Pane::Solution => Color::Blue,
+    let boards = state.boards(state.date_pane.selected).clone();
+    if !boards.is_empty() {
+        let theme = &state.options.theme;
+        let (color, selection) = match state.focused_pane {
+            Pane::Date     => (theme.unfocused, theme.unfocused),
+            Pane::Solution => (theme.focused, theme.selection),
         };
+        let text = theme.text;
+        let selected = state.selected_solutions.get(&state.date_pane.selected).copied();
 
         for (i, board) in boards.iter().enumerate() {
             let origin = Position::from(state.solution_pane.area);
 
             let offset = Offset {
-                x: (SMALL.width as i32 + 2) * (i as i32 % state.solution_pane.num_cols as i32)
+                x: (state.small().width as i32 + 2) * (i as i32 % state.solution_pane.num_cols as i32)
                     + PADDING as i32 - 1,
-                y: SMALL.height as i32 * (i as i32 / state.solution_pane.num_cols as i32)
+                y: state.small().height as i32 * (i as i32 / state.solution_pane.num_cols as i32)
                     - state.solution_pane.scroll
                     + PADDING as i32 / 2,
             };
 
-            let mut rect = Rect::from((origin, Size { width: SMALL.width + 2, ..SMALL }))
+            let mut rect = Rect::from((origin, Size { width: state.small().width + 2, ..state.small() }))
                 .offset(offset)
                 .intersection(state.solution_pane.area);
 
@@ -53,22 +57,20 @@ pub fn draw(state: &mut State, frame: &mut Frame) {
                 continue;
             }
 
-            let thumbnail = Paragraph::new(board.to_mini_string())
-                .style(Style::default().fg(color))
+            let style = if selected == Some(i) {
+                Style::default().fg(text).bg(selection)
+            } else {
+                Style::default().fg(color)
+            };
+            let thumbnail = Paragraph::new(state.mini_board_text(board))
+                .style(style)
                 .block(Block::new().padding(Padding::horizontal(1)))
-                .scroll(if rect.y == origin.y { (SMALL.height - rect.height, 0) } else { (0, 0) });
+                .scroll(if rect.y == origin.y { (state.small().height - rect.height, 0) } else { (0, 0) });
 
             frame.render_widget(thumbnail, rect);
 
             state.solution_pane.buttons.insert(i, rect);
         }
-
-        if let Some(&selected) = state.selected_solutions.get(&state.date_pane.selected) {
-            if let Some((_, &rect)) = state.solution_pane.buttons.iter().find(|&(&index, _)| index == selected) {
-                let block = Block::new().style(Style::default().fg(Color::Black).bg(color));
-                frame.render_widget(block, rect);
-            }
-        }
     }
 }
 
@@ -145,8 +147,8 @@ pub fn center_selection(state: &mut State) {
         None => { state.solution_pane.scroll = 0; }
         Some(&index) => {
             let row_num = index as u16 / state.solution_pane.num_cols;
-            let center_y = state.solution_pane.area.top() + state.solution_pane.area.height / 2 - SMALL.height / 2 - (PADDING + 1) / 2;
-            state.solution_pane.scroll = (row_num * SMALL.height) as i32 - center_y as i32;
+            let center_y = state.solution_pane.area.top() + state.solution_pane.area.height / 2 - state.small().height / 2 - PADDING.div_ceil(2);
+            state.solution_pane.scroll = (row_num * state.small().height) as i32 - center_y as i32;
             clamp_scroll(state);
         }
     }
@@ -157,8 +159,8 @@ pub fn scroll_to_selection(state: &mut State) {
         None => { state.solution_pane.scroll = 0; }
         Some(&index) => {
             let row_num = index as u16 / state.solution_pane.num_cols;
-            let selected_y = (row_num * SMALL.height) as i32 - state.solution_pane.scroll;
-            let bottom_y = (state.solution_pane.area.height - SMALL.height - PADDING + PADDING % 2) as i32;
+            let selected_y = (row_num * state.small().height) as i32 - state.solution_pane.scroll;
+            let bottom_y = (state.solution_pane.area.height - state.small().height - PADDING + PADDING % 2) as i32;
             if selected_y < 0 {
                 state.solution_pane.scroll += selected_y;
                 clamp_scroll(state);
@@ -176,7 +178,7 @@ fn clamp_scroll(state: &mut State) {
         Some(&num_solutions) => 1 + (num_solutions as u16 - 1) / state.solution_pane.num_cols
     };
 
-    let max_scroll = (SMALL.height * num_rows + PADDING + 1)
+    let max_scroll = (state.small().height * num_rows + PADDING + 1)
         .saturating_sub(state.solution_pane.area.bottom() + PADDING % 2);
 
     state.solution_pane.scroll = state.solution_pane.scroll
@@ -0,0 +1,175 @@
+use crate::board::Piece;
+use crate::render::{PIECE_COLORS, DATE_COLOR, Rgb};
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use clap::ValueEnum;
+use serde::Deserialize;
+use ratatui::style::Color;
+
+/// Colors used to draw the TUI.
+#[derive(Clone, Debug)]
+pub struct Theme {
+    /// Border and boards of the focused pane.
+    pub focused: Color,
+    /// Border and boards of the other pane, and its selected board.
+    pub unfocused: Color,
+    /// Background of the selected board in the focused pane.
+    pub selection: Color,
+    /// Text drawn on the selection and on key hints.
+    pub text: Color,
+    /// Fill of each piece, in the order of [`Piece::pieces`].
+    pub pieces: [Color; Piece::COUNT],
+    /// Fill of the uncovered date squares of a solution.
+    pub date: Color,
+}
+
+#[derive(Clone, Copy, ValueEnum, Deserialize, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub enum Preset {
+    /// For terminals with a dark background
+    Dark,
+    /// For terminals with a light background
+    Light,
+    /// Bold colors from the basic sixteen
+    HighContrast,
+}
+
+fn rgb(color: Rgb) -> Color {
+    Color::Rgb(color.0, color.1, color.2)
+}
+
+impl Theme {
+    pub fn preset(preset: Preset) -> Self {
+        match preset {
+            Preset::Dark => Theme {
+                focused: Color::Blue,
+                unfocused: Color::DarkGray,
+                selection: Color::Blue,
+                text: Color::Black,
+                pieces: PIECE_COLORS.map(rgb),
+                date: rgb(DATE_COLOR),
+            },
+            Preset::Light => Theme {
+                focused: Color::Blue,
+                unfocused: Color::Gray,
+                selection: Color::Blue,
+                text: Color::White,
+                pieces: PIECE_COLORS.map(rgb),
+                date: rgb(DATE_COLOR),
+            },
+            Preset::HighContrast => Theme {
+                focused: Color::LightYellow,
+                unfocused: Color::Gray,
+                selection: Color::LightYellow,
+                text: Color::Black,
+                pieces: [
+                    Color::LightRed, Color::LightMagenta, Color::LightGreen, Color::LightCyan,
+                    Color::LightBlue, Color::Magenta, Color::Green, Color::White,
+                    Color::Yellow, Color::Cyan, Color::Red,
+                ],
+                date: Color::Yellow,
+            },
+        }
+    }
+
+    /// Location of the theme file, `theme.toml` in this program's directory within the user's config directory.
+    pub fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("a-puzzle-a-day").join("theme.toml"))
+    }
+
+    /// The given preset, or else the theme in the theme file, if there is one, or else the dark preset.
+    pub fn load(preset: Option<Preset>) -> Result<Self, ThemeError> {
+        if let Some(preset) = preset {
+            return Ok(Theme::preset(preset));
+        }
+
+        let Some(path) = Theme::path().filter(|path| path.exists()) else {
+            return Ok(Theme::preset(Preset::Dark));
+        };
+        let contents = fs::read_to_string(&path).map_err(|err| ThemeError::Io(path.clone(), err))?;
+        Theme::from_toml(&contents).map_err(|err| ThemeError::Parse(path, err))
+    }
+
+    /// Reads a theme from TOML, where any color left out is taken from the preset named by `base`
+    /// (the dark preset by default). Piece colors are given in a `pieces` table keyed by letter.
+    ///
+    /// Colors are given by name (e.g. `"blue"`), as a hex code (e.g. `"#5f87af"`), or by index (e.g. `"8"`).
+    pub fn from_toml(contents: &str) -> Result<Self, toml::de::Error> {
+        let file = toml::from_str::<ThemeFile>(contents)?;
+        let base = Theme::preset(file.base.unwrap_or(Preset::Dark));
+
+        let mut pieces = base.pieces;
+        if let Some(file_pieces) = file.pieces {
+            let colors = [
+                file_pieces.l, file_pieces.n, file_pieces.o, file_pieces.p,
+                file_pieces.u, file_pieces.v, file_pieces.y, file_pieces.z,
+                file_pieces.i, file_pieces.j, file_pieces.s,
+            ];
+            for (piece, color) in pieces.iter_mut().zip(colors) {
+                *piece = color.unwrap_or(*piece);
+            }
+        }
+
+        Ok(Theme {
+            focused: file.focused.unwrap_or(base.focused),
+            unfocused: file.unfocused.unwrap_or(base.unfocused),
+            selection: file.selection.unwrap_or(base.selection),
+            text: file.text.unwrap_or(base.text),
+            pieces,
+            date: file.date.unwrap_or(base.date),
+        })
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::preset(Preset::Dark)
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+struct ThemeFile {
+    base: Option<Preset>,
+    focused: Option<Color>,
+    unfocused: Option<Color>,
+    selection: Option<Color>,
+    text: Option<Color>,
+    pieces: Option<PieceColors>,
+    date: Option<Color>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields, rename_all = "UPPERCASE")]
+struct PieceColors {
+    l: Option<Color>,
+    n: Option<Color>,
+    o: Option<Color>,
+    p: Option<Color>,
+    u: Option<Color>,
+    v: Option<Color>,
+    y: Option<Color>,
+    z: Option<Color>,
+    i: Option<Color>,
+    j: Option<Color>,
+    s: Option<Color>,
+}
+
+#[derive(Debug)]
+pub enum ThemeError {
+    Io(PathBuf, io::Error),
+    Parse(PathBuf, toml::de::Error),
+}
+
+impl fmt::Display for ThemeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ThemeError::Io(path, err) => write!(f, "could not read theme `{}`: {err}", path.display()),
+            ThemeError::Parse(path, err) => write!(f, "invalid theme `{}`: {err}", path.display()),
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use super::theme::Theme;
+
+use crate::board::{Board, Date, Piece};
+use crate::render::INK_COLOR;
+
+use ratatui::text::{Line, Span, Text};
+use ratatui::style::{Style, Color};
+
+/// What fills a square: the piece covering it, or nothing for a square of the date.
+type Region = Option<Piece>;
+
+/// Colors the squares of boards drawn as text.
+struct Fills<'a> {
+    board: &'a Board,
+    theme: &'a Theme,
+    date: Option<Date>,
+}
+
+impl Fills<'_> {
+    /// Region and fill of the square at (`row`, `col`), if it exists and is to be filled.
+    fn at(&self, row: isize, col: isize) -> Option<(Region, Color)> {
+        let square = self.board.frame().square_at(row, col)?;
+        match self.board.piece_at(square) {
+            Some(piece) => Some((Some(piece), self.theme.pieces[self.board.frame().color_index(piece)])),
+            None if self.date.is_some_and(|date| date.squares().contains(&square)) => {
+                Some((None, self.theme.date))
+            }
+            None => None,
+        }
+    }
+
+    /// Fill of a character on the boundary of the given squares, if they are all the same region.
+    fn joined(&self, squares: &[(isize, isize)]) -> Option<(Region, Color)> {
+        let first = self.at(squares[0].0, squares[0].1)?;
+        squares[1..].iter().all(|&(row, col)| self.at(row, col) == Some(first)).then_some(first)
+    }
+
+    fn style(&self, fill: Option<(Region, Color)>) -> Style {
+        match fill {
+            Some((Some(_), color)) => Style::default().bg(color),
+            Some((None, color)) => Style::default().fg(Color::Rgb(INK_COLOR.0, INK_COLOR.1, INK_COLOR.2)).bg(color),
+            None => Style::default(),
+        }
+    }
+}
+
+/// Styles `drawing` of a board, as written by `Display` for [`Board`], filling each piece with its color
+/// and highlighting the uncovered squares of `date`.
+pub fn big(board: &Board, date: Option<Date>, drawing: String, theme: &Theme) -> Text<'static> {
+    let fills = Fills { board, theme, date };
+
+    // After the top border, lines alternate between corners of squares and their middles,
+    // and every square is four characters wide, the first being its left edge.
+    styled(drawing, |line, x| {
+        if line == 0 || x < 2 {
+            return Style::default();
+        }
+        let (row, corner) = ((line as isize - 1) / 2, (line - 1) % 2 == 0);
+        let (col, edge) = ((x as isize - 2) / 4, (x - 2) % 4 == 0);
+        fills.style(match (corner, edge) {
+            (false, false) => fills.at(row, col),
+            (false, true) => fills.joined(&[(row, col - 1), (row, col)]),
+            (true, false) => fills.joined(&[(row - 1, col), (row, col)]),
+            (true, true) => fills.joined(&[(row - 1, col - 1), (row - 1, col), (row, col - 1), (row, col)]),
+        })
+    })
+}
+
+/// Styles `drawing` of a board, as written by [`Board::to_mini_string`], in the same way as [`big`].
+pub fn small(board: &Board, date: Option<Date>, drawing: String, theme: &Theme) -> Text<'static> {
+    let fills = Fills { board, theme, date };
+
+    // Each line only draws corners, so every square is given the two characters of its top left corner.
+    styled(drawing, |line, x| fills.style(fills.at(line as isize, x as isize / 2)))
+}
+
+/// Splits each line of `drawing` into spans of characters with the same style.
+fn styled(drawing: String, style: impl Fn(usize, usize) -> Style) -> Text<'static> {
+    let lines = drawing.lines().enumerate().map(|(i, line)| {
+        let mut spans: Vec<Span> = Vec::new();
+        for (x, c) in line.chars().enumerate() {
+            let style = style(i, x);
+            match spans.last_mut() {
+                Some(span) if span.style == style => span.content.to_mut().push(c),
+                _ => spans.push(Span::styled(c.to_string(), style)),
+            }
+        }
+        Line::from(spans)
+    });
+    Text::from(lines.collect::<Vec<_>>())
+}
@@ -0,0 +1,95 @@
+use crate::board::Frame;
+use crate::export;
+use crate::solutions;
+use crate::stats::DateCount;
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use indicatif::MultiProgress;
+use serde::Serialize;
+
+/// Dates listed on each line of the report.
+const DATES_PER_LINE: usize = 6;
+
+/// Number of dates with a given number of solutions.
+#[derive(Serialize, Clone, Copy, Debug)]
+pub struct Bucket {
+    pub solutions: usize,
+    pub dates: usize,
+}
+
+/// Number of solutions for every date of a frame, found by solving each date in turn.
+#[derive(Serialize, Debug)]
+pub struct Calendar {
+    pub frame: String,
+    pub pieces: String,
+    pub total: usize,
+    /// Dates with exactly one solution, in order.
+    pub unique: Vec<DateCount>,
+    /// Dates without any solution, in order.
+    pub impossible: Vec<DateCount>,
+    /// Number of dates with each number of solutions, fewest solutions first.
+    pub distribution: Vec<Bucket>,
+    /// Every date of the frame, in order.
+    pub counts: Vec<DateCount>,
+}
+
+impl Calendar {
+    /// Solves every date, and weekday if the frame has them, whose squares are all in `frame`.
+    pub fn solve(frame: Frame, progress: Option<&MultiProgress>) -> Self {
+        let dates = export::dates(frame, None, None).into_iter()
+            .filter(|date| date.squares().into_iter().all(|square| frame.contains(square)))
+            .collect::<Vec<_>>();
+        let counts = solutions::count_for(frame, &dates, progress).into_iter()
+            .zip(dates)
+            .map(|(count, date)| DateCount::new(date, count))
+            .collect::<Vec<_>>();
+
+        let mut distribution = BTreeMap::<usize, usize>::new();
+        for date in counts.iter() {
+            *distribution.entry(date.count()).or_default() += 1;
+        }
+
+        Calendar {
+            frame: frame.name().to_string(),
+            pieces: frame.pieces().iter().map(|piece| piece.letter()).collect(),
+            total: counts.iter().map(DateCount::count).sum(),
+            unique: counts.iter().filter(|date| date.count() == 1).cloned().collect(),
+            impossible: counts.iter().filter(|date| date.count() == 0).cloned().collect(),
+            distribution: distribution.into_iter().map(|(solutions, dates)| Bucket { solutions, dates }).collect(),
+            counts,
+        }
+    }
+
+    /// The unique and impossible dates and the distribution of solutions, as text.
+    pub fn report(&self) -> String {
+        let mut report = String::new();
+        let _ = writeln!(
+            report, "{} solutions over {} dates of the {} frame, with pieces {}",
+            self.total, self.counts.len(), self.frame, self.pieces,
+        );
+
+        for (title, none, dates) in [
+            ("Dates with a single solution", "No date has a single solution", &self.unique),
+            ("Dates without a solution", "Every date has a solution", &self.impossible),
+        ] {
+            if dates.is_empty() {
+                let _ = writeln!(report, "\n{none}");
+                continue;
+            }
+            let _ = writeln!(report, "\n{title} ({}):", dates.len());
+            for line in dates.chunks(DATES_PER_LINE) {
+                let line = line.iter().map(|date| format!("{:<12}", date.date().to_string())).collect::<String>();
+                let _ = writeln!(report, "  {}", line.trim_end());
+            }
+        }
+
+        let _ = writeln!(report, "\nSolutions  Dates");
+        for bucket in self.distribution.iter() {
+            let _ = writeln!(report, "{:>9}  {:>5}", bucket.solutions, bucket.dates);
+        }
+
+        report.trim_end().to_string()
+    }
+}
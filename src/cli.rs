@@ -1,9 +1,14 @@
 use crate::board::square::{Square, Date};
+use crate::board::placement::Placement;
+use crate::board::frame::{self, Frame, FrameError};
+use crate::browse::theme::Preset;
 
+use std::fs;
 use std::path::PathBuf;
 
-use clap::{Parser, ValueEnum};
-use chrono::{Local, Datelike, Duration, Month};
+use clap::{CommandFactory, FromArgMatches, Parser, ValueEnum};
+use clap::parser::ValueSource;
+use chrono::{Local, Datelike, Duration, Month, NaiveDate, Weekday};
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -11,12 +16,121 @@ pub struct Config {
     /// Mode of use
     #[arg(value_enum, default_value_t = Mode::Browse)]
     pub mode: Mode,
-    /// Date to show solutions for
-    #[arg(short, long, value_parser = parse_date_or_today, default_value = "today")]
-    pub date: Date,
+    /// Date to show solutions for, optionally with a weekday (e.g. "Mon Feb 28") [default: today]
+    #[arg(short, long, value_parser = parse_date_or_today)]
+    pub date: Option<Date>,
+    /// Last date of a range of dates to export, starting from the date given
+    #[arg(long, value_parser = parse_date_or_today)]
+    pub to: Option<Date>,
     /// Where to look for solutions
     #[arg(short, long)]
     pub file: Option<PathBuf>,
+    /// Layout of the board and the pieces played with: classic, weekday, or a file defining a frame
+    #[arg(long, value_parser = parse_frame, default_value = "classic")]
+    pub frame: Frame,
+    /// File defining pieces to play with instead of those of the frame
+    #[arg(long)]
+    pub piece_set: Option<PathBuf>,
+    /// Number of pieces to reveal as a hint
+    #[arg(long)]
+    pub pieces: Option<usize>,
+    /// Pieces already placed, as piece@square:rotation[m] (e.g. L@Jan:1m)
+    #[arg(short, long = "place", value_delimiter = ',')]
+    pub placements: Vec<String>,
+    /// Pack generated solutions into a smaller file
+    #[arg(long)]
+    pub compress: bool,
+    /// Number of hardest and easiest dates to list in statistics
+    #[arg(long, default_value_t = 10)]
+    pub top: usize,
+    /// Write statistics or analysis as JSON
+    #[arg(long)]
+    pub json: bool,
+    /// Write piece heatmaps as CSV
+    #[arg(long)]
+    pub csv: bool,
+    /// Format of exported or imported solutions
+    #[arg(long, value_enum, default_value_t = Format::Json)]
+    pub format: Format,
+    /// Where to write exported solutions [default: standard output], or imported solutions
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+    /// Render solutions as images instead, written to the output file (or directory, when exporting)
+    #[arg(long, value_enum)]
+    pub render: Option<Render>,
+    /// Width of a square in rendered images, in pixels
+    #[arg(long, default_value_t = 60)]
+    pub cell_size: usize,
+    /// Draw with ASCII characters only, for terminals without box-drawing glyphs
+    #[arg(long)]
+    pub ascii: bool,
+    /// When to color boards shown in the terminal
+    #[arg(long, value_enum, default_value_t = ColorChoice::Auto)]
+    pub color: ColorChoice,
+    /// Color theme of the browser [default: the theme file if there is one, otherwise dark]
+    #[arg(long, value_enum)]
+    pub theme: Option<Preset>,
+    /// Where to read solutions to import [default: standard input]
+    #[arg(short, long)]
+    pub input: Option<PathBuf>,
+    /// Whether the frame was chosen by `--frame` or `--piece-set` rather than left as the default
+    #[arg(skip)]
+    pub frame_given: bool,
+}
+
+impl Config {
+    /// Parses the command line, noting whether the frame was chosen on it.
+    pub fn from_args() -> Self {
+        let matches = Config::command().get_matches();
+        let mut config = Config::from_arg_matches(&matches).unwrap_or_else(|err| err.exit());
+        config.frame_given = matches.value_source("frame") == Some(ValueSource::CommandLine) || config.piece_set.is_some();
+        config
+    }
+
+    /// The date given, or today if none was, as a date of the frame given.
+    pub fn date(&self) -> Date {
+        self.date_in(self.frame)
+    }
+
+    /// The placements given, parsed only once every piece defined by the frame and piece set given is known.
+    pub fn placements(&self) -> Result<Vec<Placement>, String> {
+        self.placements.iter().map(|value| parse_placement(value)).collect()
+    }
+
+    /// Replaces the pieces of the frame given with those of the piece set given, if any.
+    pub fn load_piece_set(&mut self) -> Result<(), String> {
+        let Some(file) = &self.piece_set else { return Ok(()); };
+        let text = fs::read_to_string(file).map_err(|err| format!("could not read `{}`: {err}", file.display()))?;
+        self.frame = frame::parse_pieces(&text)
+            .and_then(|pieces| self.frame.with_pieces(pieces))
+            .map_err(|err| format!("`{}`: {err}", file.display()))?;
+        Ok(())
+    }
+
+    /// The date given, or today if none was, as a date of `frame`. See [`date_in`].
+    pub fn date_in(&self, frame: Frame) -> Date {
+        date_in(self.date.unwrap_or_else(|| parse_today("").expect("today should be a valid date")), frame)
+    }
+}
+
+/// `date` without its weekday if `frame` has none, or with the weekday it next falls on if `frame` has them
+/// but `date` was given without one.
+pub fn date_in(date: Date, frame: Frame) -> Date {
+    if !frame.has_weekdays() {
+        return Date { weekday: None, ..date };
+    }
+    if date.weekday.is_some() {
+        return date;
+    }
+
+    let month = date.month as u32 + if date.month >= Square::Jul { 0 } else { 1 };
+    let day = (date.day as u8 - Square::D01 as u8 + 1) as u32;
+    let today = Local::now().date_naive();
+    let weekday = (today.year()..today.year() + 8)
+        .filter_map(|year| NaiveDate::from_ymd_opt(year, month, day))
+        .find(|&date| date >= today)
+        .map(|date| weekday_to_square(date.weekday()));
+    Date { weekday, ..date }
 }
 
 #[derive(Clone, ValueEnum, Debug)]
@@ -25,8 +139,65 @@ pub enum Mode {
     Generate,
     /// Browse solutions
     Browse,
+    /// Solve the puzzle by placing pieces in the terminal
+    Play,
     /// Show random solution
     Random,
+    /// Find solutions for date without solution set
+    Solve,
+    /// Show part of a solution, one more piece each time
+    Hint,
+    /// Check whether placed pieces can be completed to a solution
+    Complete,
+    /// Check solution file for corruption
+    Verify,
+    /// Write solutions with their placements as JSON or CSV
+    Export,
+    /// Read solutions written by export into a solution file
+    Import,
+    /// Show how many solutions each date has, and which dates are hardest
+    Stats,
+    /// Show where each piece lies and how it is turned across solutions
+    Heatmap,
+    /// Solve every date, listing those with a single solution or none
+    Analyze,
+}
+
+#[derive(Clone, Copy, ValueEnum, Debug)]
+pub enum ColorChoice {
+    /// Color if writing to a terminal
+    Auto,
+    /// Always color
+    Always,
+    /// Never color
+    Never,
+}
+
+#[derive(Clone, Copy, ValueEnum, Debug)]
+pub enum Render {
+    /// Scalable vector graphics
+    Svg,
+    /// Portable network graphics
+    Png,
+}
+
+impl Render {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Render::Svg => "svg",
+            Render::Png => "png",
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum, Debug)]
+pub enum Format {
+    /// A single JSON array
+    Json,
+    /// One JSON object per line
+    Ndjson,
+    /// One row per solution
+    Csv,
 }
 
 fn parse_today(offset: &str) -> Result<Date, String> {
@@ -50,9 +221,19 @@ fn parse_today(offset: &str) -> Result<Date, String> {
             .map_err(|_| "problem determining month from system time")?,
         day: Square::try_from(day)
             .map_err(|_| "problem determining day from system time")?,
+        weekday: Some(weekday_to_square(date.weekday())),
     })
 }
 
+fn weekday_to_square(weekday: Weekday) -> Square {
+    match weekday {
+        Weekday::Sun => Square::Sun, Weekday::Mon => Square::Mon,
+        Weekday::Tue => Square::Tue, Weekday::Wed => Square::Wed,
+        Weekday::Thu => Square::Thu, Weekday::Fri => Square::Fri,
+        Weekday::Sat => Square::Sat,
+    }
+}
+
 fn month_to_square(month: Month) -> Square {
     match month {
         Month::January   => Square::Jan, Month::February  => Square::Feb,
@@ -65,6 +246,17 @@ fn month_to_square(month: Month) -> Square {
 }
 
 fn parse_date(value: &str) -> Result<Date, String> {
+    let mut weekday = None;
+    let mut words = Vec::new();
+    for word in value.split([' ', ',']).filter(|word| !word.is_empty()) {
+        match word.parse::<Weekday>() {
+            Ok(day) if weekday.is_none() => { weekday = Some(weekday_to_square(day)); }
+            _ => { words.push(word); }
+        }
+    }
+    let value = words.join(" ");
+    let value = value.as_str();
+
     let month_str = value.split(|c: char| !c.is_alphabetic()).next().ok_or("could not determine month")?;
     let month = month_str.parse::<Month>().map_err(|_| "invalid month")?;
 
@@ -80,6 +272,7 @@ fn parse_date(value: &str) -> Result<Date, String> {
     let date = Date {
         month: month_to_square(month),
         day: Square::try_from((day + 13) as u8).expect("day check should limit to range 1-31"),
+        weekday,
     };
 
     if date.is_valid() {
@@ -93,7 +286,23 @@ pub fn parse_date_or_today(value: &str) -> Result<Date, String> {
     let normalized = value.trim().to_lowercase();
     match normalized.as_str().strip_prefix("today") {
         Some(offset) => parse_today(offset),
-        _ if value.starts_with(|c| c == '+' || c == '-') => parse_today(normalized.as_str()),
+        _ if value.starts_with(['+', '-']) => parse_today(normalized.as_str()),
         _ => parse_date(normalized.as_str()),
     }
 }
+
+/// Parses the name of a built-in frame, or reads the definition of a frame from the file at `value`.
+pub fn parse_frame(value: &str) -> Result<Frame, String> {
+    match value {
+        "classic" => Ok(Frame::classic()),
+        "weekday" => Ok(Frame::weekday()),
+        path => {
+            let text = fs::read_to_string(path).map_err(|err| format!("could not read `{path}`: {err}"))?;
+            text.parse().map_err(|err: FrameError| format!("`{path}`: {err}"))
+        }
+    }
+}
+
+pub fn parse_placement(value: &str) -> Result<Placement, String> {
+    value.parse::<Placement>().map_err(|err| err.to_string())
+}
@@ -0,0 +1,135 @@
+use crate::board::{Board, Date, Frame, Square, Placement, PlacementError};
+use crate::cli::{Format, Render};
+use crate::render;
+
+use std::io::{self, Write};
+use std::fs;
+use std::path::Path;
+
+use serde::{Serialize, Deserialize};
+
+/// A solution as exported, also read back when importing.
+#[derive(Serialize, Deserialize, Debug)]
+pub(crate) struct Solution {
+    pub(crate) month: String,
+    pub(crate) day: u8,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) weekday: Option<String>,
+    pub(crate) placements: Vec<PlacementRecord>,
+    #[serde(default)]
+    pub(crate) grid: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub(crate) struct PlacementRecord {
+    pub(crate) piece: char,
+    pub(crate) square: String,
+    pub(crate) rotation: u8,
+    pub(crate) mirror: bool,
+}
+
+impl From<Placement> for PlacementRecord {
+    fn from(placement: Placement) -> Self {
+        PlacementRecord {
+            piece: placement.piece.letter(),
+            square: placement.square.to_string(),
+            rotation: placement.rotation as u8,
+            mirror: placement.mirror,
+        }
+    }
+}
+
+impl Solution {
+    fn new(date: Date, board: &Board) -> Result<Self, PlacementError> {
+        Ok(Solution {
+            month: date.month.to_string(),
+            day: date.day as u8 - Square::D01 as u8 + 1,
+            weekday: date.weekday.map(|weekday| weekday.to_string()),
+            placements: board.placements()?.into_iter().map(PlacementRecord::from).collect(),
+            grid: board.letter_grid().iter().map(|row| row.iter().collect()).collect(),
+        })
+    }
+}
+
+/// Dates from `from` to `to` inclusive, wrapping around the end of the year if need be.
+///
+/// Without either end, every date of the year is included, once for each weekday if `frame` has them.
+pub fn dates(frame: Frame, from: Option<Date>, to: Option<Date>) -> Vec<Date> {
+    let weekday = frame.has_weekdays().then_some(Square::Sun);
+    let first = Date { month: Square::Jan, day: Square::D01, weekday };
+    let (from, to) = match (from, to) {
+        (None, None) => (first, first.prev()),
+        (Some(from), None) => (from, from),
+        (from, Some(to)) => (from.unwrap_or(first), to),
+    };
+
+    let mut dates = vec![from];
+    while *dates.last().unwrap() != to {
+        dates.push(dates.last().unwrap().next());
+    }
+    dates
+}
+
+/// Writes `solutions` to `out` in the given format.
+pub fn export(out: &mut impl Write, format: Format, solutions: &[(Date, Board)]) -> io::Result<()> {
+    let frame = solutions.first().map_or(Frame::default(), |(_, board)| board.frame());
+    let solutions = solutions.iter()
+        .map(|(date, board)| Solution::new(*date, board))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "board does not consist of valid placements"))?;
+
+    match format {
+        Format::Json => {
+            serde_json::to_writer_pretty(&mut *out, &solutions)?;
+            writeln!(out)
+        }
+        Format::Ndjson => {
+            for solution in solutions.iter() {
+                serde_json::to_writer(&mut *out, solution)?;
+                writeln!(out)?;
+            }
+            Ok(())
+        }
+        Format::Csv => {
+            write!(out, "month,day")?;
+            if frame.has_weekdays() {
+                write!(out, ",weekday")?;
+            }
+            for piece in frame.pieces() {
+                let letter = piece.letter();
+                write!(out, ",{letter}_square,{letter}_rotation,{letter}_mirror")?;
+            }
+            writeln!(out, ",grid")?;
+
+            for solution in solutions.iter() {
+                write!(out, "{},{}", solution.month, solution.day)?;
+                if let Some(weekday) = &solution.weekday {
+                    write!(out, ",{weekday}")?;
+                }
+                for placement in solution.placements.iter() {
+                    write!(out, ",{},{},{}", placement.square, placement.rotation, placement.mirror)?;
+                }
+                writeln!(out, ",{}", solution.grid.join("/"))?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Renders each of `solutions` to its own file in `dir`, named after its date and its number among that date's solutions.
+pub fn render_all(dir: &Path, format: Render, cell_size: usize, solutions: &[(Date, Board)]) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+
+    let mut previous = None;
+    let mut number = 0;
+    for (date, board) in solutions {
+        number = if previous == Some(date) { number + 1 } else { 1 };
+        previous = Some(date);
+
+        let day = date.day as u8 - Square::D01 as u8 + 1;
+        let weekday = date.weekday.map_or(String::new(), |weekday| format!("-{}", weekday.to_string().to_lowercase()));
+        let name = format!("{}-{day:02}{weekday}-{number:03}.{}", date.month.to_string().to_lowercase(), format.extension());
+        fs::write(dir.join(name), render::render(board, format, cell_size))?;
+    }
+    Ok(())
+}
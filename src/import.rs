@@ -0,0 +1,205 @@
+use crate::board::{Board, Date, Frame, Square, Piece, Placement, Rotation};
+use crate::board::compact::CompactBoard;
+use crate::export::{Solution, PlacementRecord};
+use crate::cli::Format;
+
+use std::fmt;
+
+use serde_json::Value;
+
+#[derive(Debug)]
+pub enum Location {
+    Document,
+    Record(usize),
+    Line(usize),
+}
+
+/// Reason a solution could not be imported, along with where it was found.
+#[derive(Debug)]
+pub struct ImportError {
+    pub location: Location,
+    pub message: String,
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.location {
+            Location::Document => write!(f, "{}", self.message),
+            Location::Record(n) => write!(f, "record {n}: {}", self.message),
+            Location::Line(n) => write!(f, "line {n}: {}", self.message),
+        }
+    }
+}
+
+/// Reads solutions of `frame` written by an export in the given format, checking every one.
+///
+/// All records are checked before returning, so that every error can be reported at once.
+pub fn import(input: &str, format: Format, frame: Frame) -> Result<Vec<Board>, Vec<ImportError>> {
+    let records = match format {
+        Format::Json => match serde_json::from_str::<Vec<Value>>(input) {
+            Ok(values) => values.into_iter().enumerate()
+                .map(|(i, value)| (Location::Record(i + 1), from_value(value)))
+                .collect(),
+            Err(err) => {
+                return Err(vec![ImportError { location: Location::Document, message: err.to_string() }]);
+            }
+        },
+        Format::Ndjson => lines(input)
+            .map(|(n, line)| (Location::Line(n), from_json_line(line)))
+            .collect(),
+        Format::Csv => from_csv(input, frame),
+    };
+
+    let mut boards = Vec::with_capacity(records.len());
+    let mut errors = Vec::new();
+    for (location, record) in records {
+        match record.and_then(|solution| board(&solution, frame)) {
+            Ok(board) => boards.push(board),
+            Err(message) => errors.push(ImportError { location, message }),
+        }
+    }
+
+    if errors.is_empty() { Ok(boards) } else { Err(errors) }
+}
+
+fn lines(input: &str) -> impl Iterator<Item = (usize, &str)> {
+    input.lines().enumerate()
+        .map(|(i, line)| (i + 1, line))
+        .filter(|(_, line)| !line.trim().is_empty())
+}
+
+fn from_value(value: Value) -> Result<Solution, String> {
+    serde_json::from_value(value).map_err(|err| err.to_string())
+}
+
+fn from_json_line(line: &str) -> Result<Solution, String> {
+    let value = serde_json::from_str(line)
+        .map_err(|err| format!("invalid JSON at column {}", err.column()))?;
+    from_value(value)
+}
+
+fn from_csv(input: &str, frame: Frame) -> Vec<(Location, Result<Solution, String>)> {
+    let mut lines = lines(input);
+    let Some((header_line, header)) = lines.next() else { return Vec::new(); };
+    let columns = header.split(',').map(str::trim).collect::<Vec<_>>();
+
+    let mut required = vec!["month".to_string(), "day".to_string()];
+    if frame.has_weekdays() {
+        required.push("weekday".to_string());
+    }
+    for piece in frame.pieces() {
+        let letter = piece.letter();
+        required.extend([format!("{letter}_square"), format!("{letter}_rotation"), format!("{letter}_mirror")]);
+    }
+    if let Some(missing) = required.iter().find(|name| !columns.contains(&name.as_str())) {
+        return vec![(Location::Line(header_line), Err(format!("missing column `{missing}`")))];
+    }
+
+    lines.map(|(n, line)| {
+        let fields = line.split(',').collect::<Vec<_>>();
+        let record = if fields.len() != columns.len() {
+            Err(format!("expected {} fields, found {}", columns.len(), fields.len()))
+        } else {
+            from_csv_fields(frame, &columns, &fields)
+        };
+        (Location::Line(n), record)
+    }).collect()
+}
+
+fn from_csv_fields(frame: Frame, columns: &[&str], fields: &[&str]) -> Result<Solution, String> {
+    let field = |name: &str| -> &str {
+        let i = columns.iter().position(|&column| column == name).expect("required columns should be present");
+        fields[i]
+    };
+    let parse = |name: &str| -> Result<u8, String> {
+        field(name).trim().parse().map_err(|_| format!("invalid {name} `{}`", field(name).trim()))
+    };
+
+    let mut placements = Vec::new();
+    for piece in frame.pieces() {
+        let letter = piece.letter();
+        let mirror_column = format!("{letter}_mirror");
+        let mirror = match field(&mirror_column).trim() {
+            "true" => true,
+            "false" => false,
+            other => return Err(format!("invalid {mirror_column} `{other}`")),
+        };
+        placements.push(PlacementRecord {
+            piece: letter,
+            square: field(&format!("{letter}_square")).trim().to_string(),
+            rotation: parse(&format!("{letter}_rotation"))?,
+            mirror,
+        });
+    }
+
+    let grid = if columns.contains(&"grid") {
+        field("grid").split('/').map(str::to_string).collect()
+    } else {
+        Vec::new()
+    };
+
+    Ok(Solution {
+        month: field("month").trim().to_string(),
+        day: parse("day")?,
+        weekday: frame.has_weekdays().then(|| field("weekday").trim().to_string()),
+        placements,
+        grid,
+    })
+}
+
+fn placement(record: &PlacementRecord) -> Result<Placement, String> {
+    let piece = record.piece.to_string().parse::<Piece>()
+        .map_err(|_| format!("unknown piece `{}`", record.piece))?;
+    let square = record.square.parse::<Square>()
+        .map_err(|_| format!("unknown square `{}` for piece {}", record.square, record.piece))?;
+    if record.rotation > 3 {
+        return Err(format!("rotation {} of piece {} is not in range 0-3", record.rotation, record.piece));
+    }
+    Ok(Placement { piece, square, rotation: Rotation::from(record.rotation), mirror: record.mirror })
+}
+
+/// Places the pieces of `solution` on a board of `frame`, checking the board against its date and grid.
+fn board(solution: &Solution, frame: Frame) -> Result<Board, String> {
+    let month = solution.month.parse::<Square>().ok().filter(|&month| month <= Square::Dec)
+        .ok_or_else(|| format!("unknown month `{}`", solution.month))?;
+    let weekday = solution.weekday.as_ref().map(|weekday| {
+        weekday.parse::<Square>().ok().filter(|weekday| weekday.is_weekday())
+            .ok_or_else(|| format!("unknown weekday `{weekday}`"))
+    }).transpose()?;
+    let date = Square::try_from(solution.day.wrapping_add(Square::D01 as u8 - 1)).ok()
+        .filter(Square::is_day)
+        .map(|day| Date { month, day, weekday })
+        .filter(Date::is_valid)
+        .ok_or_else(|| format!("invalid date {} {}", solution.month, solution.day))?;
+
+    match (weekday, frame.has_weekdays()) {
+        (None, true) => return Err(format!("{} frame needs a weekday", frame.name())),
+        (Some(weekday), false) => return Err(format!("{} frame has no weekdays, but {weekday} is given", frame.name())),
+        _ => {}
+    }
+
+    let mut board = Board::new(frame);
+    for record in solution.placements.iter() {
+        let placement = placement(record)?;
+        board = board.apply(&placement).ok_or_else(|| format!("{placement} does not fit on the board"))?;
+    }
+
+    CompactBoard::try_from(board.clone())
+        .map_err(|_| "board does not have every piece placed exactly once".to_string())?;
+
+    match board.solved_for() {
+        Some(solved) if solved == date => {}
+        Some(solved) => return Err(format!("board solves {solved}, not {date}")),
+        None => return Err(format!("board does not solve {date}")),
+    }
+
+    // Squares outside the frame are spaces, which may have been stripped from the end of a row.
+    if !solution.grid.is_empty() {
+        let grid = board.letter_grid().iter().map(|row| row.iter().collect::<String>()).collect::<Vec<_>>();
+        if !solution.grid.iter().map(|row| row.trim_end()).eq(grid.iter().map(|row| row.trim_end())) {
+            return Err("grid does not match placements".to_string());
+        }
+    }
+
+    Ok(board)
+}
@@ -2,3 +2,9 @@ pub mod board;
 pub mod solutions;
 pub mod cli;
 pub mod browse;
+pub mod export;
+pub mod import;
+pub mod render;
+pub mod stats;
+pub mod analysis;
+pub mod calendar;
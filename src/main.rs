@@ -1,15 +1,21 @@
 use a_puzzle_a_day::board::*;
-use a_puzzle_a_day::solutions;
-use a_puzzle_a_day::browse;
+use a_puzzle_a_day::solutions::{self, SolutionIndex};
+use a_puzzle_a_day::browse::{self, theme::Theme};
+use a_puzzle_a_day::export;
+use a_puzzle_a_day::import;
+use a_puzzle_a_day::stats::Stats;
+use a_puzzle_a_day::analysis::Analysis;
+use a_puzzle_a_day::calendar::Calendar;
+use a_puzzle_a_day::render::{self, ansi::Depth};
 use a_puzzle_a_day::cli::*;
 
 use std::process;
 use std::path::PathBuf;
-use std::fs::File;
-use std::io::Read;
+use std::fs;
+use std::env;
+use std::io::{self, Read, Write};
 
 use rand::seq::SliceRandom;
-use clap::Parser;
 use indicatif::{ProgressBar, ProgressStyle, MultiProgress};
 use dialoguer::Confirm;
 
@@ -18,25 +24,17 @@ fn error(msg: &str) -> ! {
     process::exit(1);
 }
 
-fn generate(file: PathBuf) -> Vec<Board> {
+fn generate(file: PathBuf, compress: bool, frame: Frame) -> SolutionIndex {
     let progress = MultiProgress::new();
-    
-    use Piece::*;
-    let pieces = vec![O, Z, V, U, Y, N, P, L];
-    let boards =
-        solutions::generate(Board::default(), pieces, Some(&progress))
-            .unwrap_or_else(|_| error("error encountered while generating solutions"));
+
+    let solutions = solutions::generate(frame, Some(&progress));
 
     let bar = progress.add(ProgressBar::new_spinner());
     bar.set_style(
         ProgressStyle::with_template("{spinner:.blue} {human_pos:.bold.dim} valid solutions found")
             .unwrap().tick_chars("⠇⡆⣄⣠⢰⠸⠙⠋⣿")
     );
-
-    let mut solutions = boards.iter().filter(|board|
-        board.solved_for().map(|_| bar.inc(1)).is_some()
-    ).collect::<Vec<_>>();
-    solutions.sort_unstable();
+    bar.set_position(solutions.total() as u64);
 
     bar.finish();
     bar.set_style(
@@ -47,67 +45,337 @@ fn generate(file: PathBuf) -> Vec<Board> {
 
     progress.set_move_cursor(true);
 
-    match solutions::write_boards(solutions.clone(), file) {
+    match solutions::write_index(&solutions, file, compress) {
         Ok(_) => {
             println!("\x1b[33m⣿ Finished\x1b[0m");
             solutions
         }
-        Err(_) => error("error encountered while writing solutions to file")
+        Err(err) => error(&format!("error encountered while writing solutions to file: {err}"))
+    }
+}
+
+fn show(board: &Board, depth: Option<Depth>, ascii: bool) -> String {
+    match depth {
+        Some(depth) => render::ansi::render(board, depth),
+        None if ascii => board.to_ascii_string(),
+        None => board.to_string(),
     }
 }
 
-fn get_solutions(file: Option<PathBuf>) -> DateMap<Vec<Board>> {
-    solutions::classify(match file {
-        None => {
-            solutions::read_boards(solutions::SOLUTIONS)
-                .unwrap_or_else(|_| error("error encountered when decoding solutions"))
+/// File solutions for `frame` are generated into, and read from, when none is given.
+fn default_file(frame: Frame) -> PathBuf {
+    if frame == Frame::classic() {
+        PathBuf::from("solutions.apad")
+    } else {
+        PathBuf::from(format!("{}.apad", frame.name()))
+    }
+}
+
+/// Exits if the frame was chosen on the command line but differs from `found`, that of the solutions read.
+fn check_frame(config: &Config, found: Frame) {
+    if config.frame_given && found != config.frame {
+        error(&format!("error: solutions are of the {} frame, not of the {} frame given", found.name(), config.frame.name()));
+    }
+}
+
+fn get_solutions(config: &Config) -> SolutionIndex {
+    let frame = config.frame;
+    let solutions = match config.file.clone() {
+        None if frame == Frame::classic() => {
+            SolutionIndex::from_bytes(solutions::SOLUTIONS)
+                .unwrap_or_else(|err| error(&format!("error encountered when decoding solutions: {err}")))
         }
-        Some(file) => {
+        file => {
+            let file = file.unwrap_or_else(|| default_file(frame));
             if file.as_path().exists() {
-                let handle = File::open(file).unwrap_or_else(|err| error(&format!("error: {err})")));
-                let bytes: Vec<u8> = handle.bytes().map(|res| res.unwrap_or_else(|err| error(&format!("error: {err}")))).collect();
-                solutions::read_boards(&bytes)
-                    .unwrap_or_else(|_| error("error encountered when decoding solutions"))
+                let bytes = fs::read(file).unwrap_or_else(|err| error(&format!("error: {err}")));
+                SolutionIndex::from_bytes(&bytes)
+                    .unwrap_or_else(|err| error(&format!("error encountered when decoding solutions: {err}")))
             } else {
                 let confirm = Confirm::new()
-                    .with_prompt(format!("File `{}` not found. Generate solutions?", file.as_path().display()))
+                    .with_prompt(format!("File `{}` not found. Generate solutions (uncompressed)?", file.as_path().display()))
                     .interact();
                 match confirm {
-                    Ok(true) => generate(file),
+                    Ok(true) => generate(file, false, frame),
                     _ => { process::exit(1); }
                 }
             }
         }
-    })
+    };
+    check_frame(config, solutions.frame());
+    solutions
+}
+
+/// Bytes of the solution file found as by [`get_solutions`], without offering to generate it if it is missing.
+fn get_bytes(config: &Config) -> Vec<u8> {
+    let bytes = match config.file.clone() {
+        None if config.frame == Frame::classic() => solutions::SOLUTIONS.to_vec(),
+        file => {
+            let file = file.unwrap_or_else(|| default_file(config.frame));
+            fs::read(&file).unwrap_or_else(|err| error(&format!("error: could not read `{}`: {err}", file.display())))
+        }
+    };
+    // Damaged files are left for the mode to report.
+    if let Ok(frame) = solutions::frame(&bytes) {
+        check_frame(config, frame);
+    }
+    bytes
+}
+
+/// The date given as a date of the frame solutions were found for, along with its solutions.
+fn get_solutions_for(config: &Config) -> (Date, Vec<Board>) {
+    let solutions = get_solutions(config);
+    let date = config.date_in(solutions.frame());
+    let boards = solutions.get(&date)
+        .unwrap_or_else(|err| error(&format!("error encountered when decoding solutions: {err}")));
+    (date, boards)
+}
+
+fn hint_count(date: Date, pieces: Option<usize>, total: usize) -> usize {
+    let path = env::temp_dir().join("a-puzzle-a-day-hint");
+
+    let previous = fs::read_to_string(&path).ok().and_then(|contents| {
+        let mut numbers = contents.split_whitespace().map(|n| n.parse::<u8>().ok());
+        let month = Square::try_from(numbers.next()??).ok()?;
+        let day = Square::try_from(numbers.next()??).ok()?;
+        let count = numbers.next()?? as usize;
+        let weekday = match numbers.next() {
+            Some(weekday) => Some(Square::try_from(weekday?).ok()?),
+            None => None,
+        };
+        Some((Date { month, day, weekday }, count))
+    });
+
+    let count = match (pieces, previous) {
+        (Some(count), _) => count,
+        (None, Some((prev_date, prev_count))) if prev_date == date => prev_count + 1,
+        _ => 1,
+    }.min(total);
+
+    let weekday = date.weekday.map_or(String::new(), |weekday| format!(" {}", weekday as u8));
+    let _ = fs::write(&path, format!("{} {} {}{weekday}", date.month as u8, date.day as u8, count));
+
+    count
 }
 
 fn main() {
-    let config = Config::parse();
+    let mut config = Config::from_args();
+    config.load_piece_set().unwrap_or_else(|err| error(&format!("error: {err}")));
+    let date = config.date();
+    let depth = Depth::from_choice(config.color);
 
     match config.mode {
         Mode::Generate => {
-            let file = config.file.unwrap_or(PathBuf::from("solutions.apad"));
-            generate(file);
+            let file = config.file.unwrap_or_else(|| default_file(config.frame));
+            generate(file, config.compress, config.frame);
         }
         Mode::Browse => {
-            let solutions = get_solutions(config.file);
-            if let Err(e) = browse::browse(solutions, config.date) {
+            let theme = Theme::load(config.theme).unwrap_or_else(|err| {
+                eprintln!("error: {err}");
+                process::exit(1);
+            });
+            let solutions = get_solutions(&config);
+            let date = config.date_in(solutions.frame());
+            let options = browse::Options { ascii: config.ascii, theme };
+            if let Err(e) = browse::browse(solutions, date, options) {
+                eprintln!("error: {e}");
+                process::exit(1);
+            }
+        }
+        Mode::Play => {
+            let theme = Theme::load(config.theme).unwrap_or_else(|err| {
+                eprintln!("error: {err}");
+                process::exit(1);
+            });
+            let options = browse::Options { ascii: config.ascii, theme };
+            if let Err(e) = browse::play::play(Board::new(config.frame), date, options) {
                 eprintln!("error: {e}");
                 process::exit(1);
             }
         }
         Mode::Random => {
-            let solutions = get_solutions(config.file);
-            match solutions.get(&config.date) {
-                Some(sols) => {
-                    let mut rng = rand::thread_rng();
-                    println!("{}", Vec::from_iter(sols).choose(&mut rng).unwrap());
+            let (date, solutions) = get_solutions_for(&config);
+            match solutions.choose(&mut rand::thread_rng()) {
+                Some(board) => match (config.render, config.output) {
+                    (None, _) => println!("{}", show(board, depth, config.ascii)),
+                    (Some(format), None) => {
+                        if let Err(err) = io::stdout().write_all(&render::render(board, format, config.cell_size)) {
+                            error(&format!("error: {err}"));
+                        }
+                    }
+                    (Some(format), Some(file)) => {
+                        if let Err(err) = fs::write(file, render::render(board, format, config.cell_size)) {
+                            error(&format!("error encountered when writing rendered solution: {err}"));
+                        }
+                    }
                 },
                 None => {
-                    eprintln!("No solutions found for date {}", config.date);
+                    eprintln!("No solutions found for date {}", date);
+                    process::exit(1);
+                }
+            }
+        }
+        Mode::Hint => {
+            let (date, solutions) = get_solutions_for(&config);
+            if solutions.is_empty() {
+                eprintln!("No solutions found for date {}", date);
+                process::exit(1);
+            }
+            let total = solutions[0].frame().pieces().len();
+            let count = hint_count(date, config.pieces, total);
+            println!("{}", show(&solutions::hint(&solutions, count), depth, config.ascii));
+            println!("{} of {} pieces placed", count, total);
+        }
+        Mode::Complete => {
+            let placements = config.placements().unwrap_or_else(|err| error(&format!("error: {err}")));
+            let board = placements.iter().fold(Board::new(config.frame), |board, placement| {
+                board.apply(placement).unwrap_or_else(|| error(&format!("error: {placement} does not fit on the board")))
+            });
+
+            if let Some(dead_end) = board.dead_end(date) {
+                println!("{}", show(&board, depth, config.ascii));
+                eprintln!("No solutions for date {} extend the placed pieces: {dead_end}", date);
+                process::exit(1);
+            }
+
+            let mut extensions = solutions::solve_from(board.clone(), date).into_iter();
+            match extensions.next() {
+                Some(example) => {
+                    let count = 1 + extensions.count();
+                    println!("{}", show(&example, depth, config.ascii));
+                    match count {
+                        1 => println!("1 solution for date {} extends the placed pieces", date),
+                        _ => println!("{count} solutions for date {} extend the placed pieces", date),
+                    }
+                }
+                None => {
+                    println!("{}", show(&board, depth, config.ascii));
+                    eprintln!("No solutions for date {} extend the placed pieces", date);
                     process::exit(1);
                 }
             }
         }
+        Mode::Verify => {
+            let bytes = get_bytes(&config);
+            match solutions::verify(&bytes) {
+                Ok(header) => {
+                    println!("APAD version {} with {} boards", header.version, header.count);
+                    if header.version == 1 {
+                        println!("No checksum to verify");
+                    } else {
+                        println!("Checksum {:08x} OK", header.checksum);
+                    }
+                }
+                Err(err) => error(&format!("error: {err}")),
+            }
+        }
+        Mode::Stats => {
+            let bytes = get_bytes(&config);
+            let solutions = SolutionIndex::from_bytes(&bytes)
+                .unwrap_or_else(|err| error(&format!("error encountered when decoding solutions: {err}")));
+
+            let stats = Stats::new(&solutions, config.top);
+            let output = if config.json {
+                serde_json::to_string_pretty(&stats).expect("statistics should serialize to JSON")
+            } else {
+                stats.report(depth)
+            };
+            match writeln!(io::stdout().lock(), "{output}") {
+                Err(err) if err.kind() != io::ErrorKind::BrokenPipe => error(&format!("error: {err}")),
+                _ => {}
+            }
+        }
+        Mode::Heatmap => {
+            let bytes = get_bytes(&config);
+            let boards = match config.date {
+                None => solutions::read_boards(&bytes),
+                Some(date) => SolutionIndex::from_bytes(&bytes)
+                    .and_then(|solutions| solutions.get(&date_in(date, solutions.frame()))),
+            }.unwrap_or_else(|err| error(&format!("error encountered when decoding solutions: {err}")));
+
+            let analysis = Analysis::new(&boards);
+            let output = if config.csv { analysis.csv() } else { analysis.report(depth, config.ascii) };
+            match writeln!(io::stdout().lock(), "{}", output.trim_end()) {
+                Err(err) if err.kind() != io::ErrorKind::BrokenPipe => error(&format!("error: {err}")),
+                _ => {}
+            }
+        }
+        Mode::Analyze => {
+            let calendar = Calendar::solve(config.frame, Some(&MultiProgress::new()));
+            let output = if config.json {
+                serde_json::to_string_pretty(&calendar).expect("analysis should serialize to JSON")
+            } else {
+                calendar.report()
+            };
+            match writeln!(io::stdout().lock(), "{output}") {
+                Err(err) if err.kind() != io::ErrorKind::BrokenPipe => error(&format!("error: {err}")),
+                _ => {}
+            }
+        }
+        Mode::Export => {
+            let solutions = get_solutions(&config);
+            let frame = solutions.frame();
+            let (from, to) = (config.date.map(|date| date_in(date, frame)), config.to.map(|date| date_in(date, frame)));
+            let mut boards = Vec::new();
+            for date in export::dates(frame, from, to) {
+                let solved = solutions.get(&date)
+                    .unwrap_or_else(|err| error(&format!("error encountered when decoding solutions: {err}")));
+                boards.extend(solved.into_iter().map(|board| (date, board)));
+            }
+
+            let result = match (config.render, config.output) {
+                (Some(format), Some(dir)) => export::render_all(&dir, format, config.cell_size, &boards),
+                (Some(_), None) => error("error: rendered solutions need an output directory, given by -o/--output"),
+                (None, None) => export::export(&mut io::stdout().lock(), config.format, &boards),
+                (None, Some(file)) => fs::File::create(file).and_then(|file| {
+                    let mut writer = io::BufWriter::new(file);
+                    export::export(&mut writer, config.format, &boards)?;
+                    writer.flush()
+                }),
+            };
+            match result {
+                Err(err) if err.kind() != io::ErrorKind::BrokenPipe =>
+                    error(&format!("error encountered when exporting solutions: {err}")),
+                _ => {}
+            }
+        }
+        Mode::Import => {
+            let Some(file) = config.output else {
+                error("error: imported solutions need an output file, given by -o/--output");
+            };
+
+            let mut input = String::new();
+            let read = match config.input {
+                None => io::stdin().read_to_string(&mut input),
+                Some(file) => fs::File::open(file).and_then(|mut file| file.read_to_string(&mut input)),
+            };
+            if let Err(err) = read {
+                error(&format!("error: {err}"));
+            }
+
+            match import::import(&input, config.format, config.frame) {
+                Ok(boards) => {
+                    let count = boards.len();
+                    if let Err(err) = solutions::write_boards(boards, file, config.compress) {
+                        error(&format!("error encountered while writing solutions to file: {err}"));
+                    }
+                    println!("{count} solutions imported");
+                }
+                Err(errors) => {
+                    for err in errors.iter() {
+                        eprintln!("error: {err}");
+                    }
+                    error("no solutions imported");
+                }
+            }
+        }
+        Mode::Solve => {
+            let mut count = 0;
+            for board in solutions::solve_for(config.frame, date) {
+                println!("{}\n", show(&board, depth, config.ascii));
+                count += 1;
+            }
+            println!("{count} solutions found for date {}", date);
+        }
     }
 }
@@ -0,0 +1,66 @@
+pub mod svg;
+pub mod png;
+pub mod ansi;
+
+use crate::board::{Board, Frame, Piece, Square};
+use crate::cli::Render;
+
+/// Color with 8-bit red, green and blue channels.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub fn hex(&self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+
+    /// This color with `over` laid on top of it with the given opacity.
+    pub fn blend(&self, over: Rgb, alpha: f32) -> Rgb {
+        let mix = |a: u8, b: u8| (a as f32 * (1.0 - alpha) + b as f32 * alpha).round() as u8;
+        Rgb(mix(self.0, over.0), mix(self.1, over.1), mix(self.2, over.2))
+    }
+}
+
+/// Fill of each piece, in the order of [`Piece::pieces`].
+pub const PIECE_COLORS: [Rgb; Piece::COUNT] = [
+    Rgb(0xd9, 0x5a, 0x5a),
+    Rgb(0xf0, 0x9a, 0xc8),
+    Rgb(0x98, 0xc3, 0x79),
+    Rgb(0x56, 0xb6, 0xc2),
+    Rgb(0x61, 0xaf, 0xef),
+    Rgb(0xc6, 0x78, 0xdd),
+    Rgb(0xd1, 0x9a, 0x66),
+    Rgb(0x9a, 0xa5, 0xb1),
+    Rgb(0xe5, 0xc0, 0x7b),
+    Rgb(0x4d, 0x9a, 0x8a),
+    Rgb(0xb4, 0x8e, 0xad),
+];
+
+/// Fill of squares not covered by any piece.
+pub const FRAME_COLOR: Rgb = Rgb(0xf5, 0xf0, 0xe6);
+/// Fill of the squares left uncovered by a solution.
+pub const DATE_COLOR: Rgb = Rgb(0xff, 0xe0, 0x82);
+/// Color of outlines and labels.
+pub const INK_COLOR: Rgb = Rgb(0x2b, 0x2b, 0x2b);
+
+/// Fill of `piece` on boards of `frame`.
+pub fn piece_color(frame: Frame, piece: Piece) -> Rgb {
+    PIECE_COLORS[frame.color_index(piece)]
+}
+
+/// Fill of `square` on `board`.
+pub fn square_color(board: &Board, square: Square) -> Rgb {
+    match board.piece_at(square) {
+        Some(piece) => piece_color(board.frame(), piece),
+        None if board.solved_for().is_some() => DATE_COLOR,
+        None => FRAME_COLOR,
+    }
+}
+
+/// Renders `board` in the given format, with squares `cell_size` pixels wide.
+pub fn render(board: &Board, format: Render, cell_size: usize) -> Vec<u8> {
+    match format {
+        Render::Svg => svg::render(board, cell_size).into_bytes(),
+        Render::Png => png::render(board, cell_size),
+    }
+}
@@ -0,0 +1,87 @@
+use super::{square_color, Rgb, INK_COLOR};
+
+use crate::board::Board;
+use crate::cli::ColorChoice;
+
+use std::env;
+
+/// Range of colors used for terminal output.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Depth {
+    /// The 256 colors of xterm.
+    Ansi256,
+    /// Any 24-bit color.
+    TrueColor,
+}
+
+impl Depth {
+    /// Truecolor if the terminal advertises it through `COLORTERM`, otherwise 256 colors.
+    pub fn detect() -> Self {
+        match env::var("COLORTERM").as_deref() {
+            Ok("truecolor" | "24bit") => Depth::TrueColor,
+            _ => Depth::Ansi256,
+        }
+    }
+
+    /// Range of colors to use for the given choice, or `None` if output should not be colored.
+    pub fn from_choice(choice: ColorChoice) -> Option<Self> {
+        match choice {
+            ColorChoice::Never => None,
+            ColorChoice::Always => Some(Depth::detect()),
+            ColorChoice::Auto => console::colors_enabled().then(Depth::detect),
+        }
+    }
+
+    fn code(&self, color: Rgb) -> String {
+        match self {
+            Depth::TrueColor => format!("2;{};{};{}", color.0, color.1, color.2),
+            Depth::Ansi256 => format!("5;{}", ansi256(color)),
+        }
+    }
+}
+
+/// Closest color of the 6x6x6 color cube of xterm.
+fn ansi256(color: Rgb) -> u8 {
+    const LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+    let level = |channel: u8| {
+        (0..LEVELS.len()).min_by_key(|&i| LEVELS[i].abs_diff(channel)).unwrap() as u8
+    };
+    16 + 36 * level(color.0) + 6 * level(color.1) + level(color.2)
+}
+
+/// Draws `board` with each square as a block of background color, colored as by [`super::svg::render`],
+/// labelled faintly if covered.
+pub fn render(board: &Board, depth: Depth) -> String {
+    let mut lines = Vec::new();
+
+    let frame = board.frame();
+    for row in 0..frame.rows() as isize {
+        for line in 0..2 {
+            let mut text = String::new();
+            for col in 0..frame.cols() as isize {
+                let Some(square) = frame.square_at(row, col) else {
+                    text.push_str("    ");
+                    continue;
+                };
+
+                let fill = square_color(board, square);
+                let (ink, weight) = match board.piece_at(square) {
+                    Some(_) => (fill.blend(INK_COLOR, 0.35), ""),
+                    None => (INK_COLOR, "1;"),
+                };
+                let label = if line == 0 { square.to_string() } else { String::new() };
+                text.push_str(&format!(
+                    "\x1b[{weight}38;{};48;{}m{label:^4}\x1b[0m", depth.code(ink), depth.code(fill)
+                ));
+            }
+            lines.push(text.trim_end().to_string());
+        }
+    }
+
+    lines.join("\n")
+}
+
+/// `text` in the ink of [`render`] on a background of `color`.
+pub fn paint(text: &str, color: Rgb, depth: Depth) -> String {
+    format!("\x1b[38;{};48;{}m{text}\x1b[0m", depth.code(INK_COLOR), depth.code(color))
+}
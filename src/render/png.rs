@@ -0,0 +1,134 @@
+use super::{square_color, Rgb, INK_COLOR};
+
+use crate::board::Board;
+
+/// Glyphs for the characters of the square labels, each row's five low bits being its pixels from left to right.
+///
+/// The last glyph is drawn for any other character.
+const FONT: [(char, [u8; 8]); 38] = [
+    ('0', [0b01110, 0b10001, 0b10011, 0b10101, 0b11001, 0b10001, 0b01110, 0b00000]),
+    ('1', [0b00100, 0b01100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110, 0b00000]),
+    ('2', [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b01000, 0b11111, 0b00000]),
+    ('3', [0b11110, 0b00001, 0b00001, 0b01110, 0b00001, 0b00001, 0b11110, 0b00000]),
+    ('4', [0b00010, 0b00110, 0b01010, 0b10010, 0b11111, 0b00010, 0b00010, 0b00000]),
+    ('5', [0b11111, 0b10000, 0b11110, 0b00001, 0b00001, 0b10001, 0b01110, 0b00000]),
+    ('6', [0b00110, 0b01000, 0b10000, 0b11110, 0b10001, 0b10001, 0b01110, 0b00000]),
+    ('7', [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b01000, 0b01000, 0b00000]),
+    ('8', [0b01110, 0b10001, 0b10001, 0b01110, 0b10001, 0b10001, 0b01110, 0b00000]),
+    ('9', [0b01110, 0b10001, 0b10001, 0b01111, 0b00001, 0b00010, 0b01100, 0b00000]),
+    ('A', [0b01110, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001, 0b00000]),
+    ('D', [0b11110, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b11110, 0b00000]),
+    ('F', [0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b10000, 0b00000]),
+    ('J', [0b00111, 0b00010, 0b00010, 0b00010, 0b00010, 0b10010, 0b01100, 0b00000]),
+    ('M', [0b10001, 0b11011, 0b10101, 0b10101, 0b10001, 0b10001, 0b10001, 0b00000]),
+    ('N', [0b10001, 0b11001, 0b10101, 0b10011, 0b10001, 0b10001, 0b10001, 0b00000]),
+    ('O', [0b01110, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110, 0b00000]),
+    ('S', [0b01111, 0b10000, 0b10000, 0b01110, 0b00001, 0b00001, 0b11110, 0b00000]),
+    ('T', [0b11111, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00000]),
+    ('W', [0b10001, 0b10001, 0b10001, 0b10101, 0b10101, 0b10101, 0b01010, 0b00000]),
+    ('a', [0b00000, 0b00000, 0b01110, 0b00001, 0b01111, 0b10001, 0b01111, 0b00000]),
+    ('b', [0b10000, 0b10000, 0b10110, 0b11001, 0b10001, 0b10001, 0b11110, 0b00000]),
+    ('c', [0b00000, 0b00000, 0b01110, 0b10000, 0b10000, 0b10001, 0b01110, 0b00000]),
+    ('d', [0b00001, 0b00001, 0b01101, 0b10011, 0b10001, 0b10001, 0b01111, 0b00000]),
+    ('e', [0b00000, 0b00000, 0b01110, 0b10001, 0b11111, 0b10000, 0b01110, 0b00000]),
+    ('g', [0b00000, 0b00000, 0b01111, 0b10001, 0b10001, 0b01111, 0b00001, 0b01110]),
+    ('h', [0b10000, 0b10000, 0b10110, 0b11001, 0b10001, 0b10001, 0b10001, 0b00000]),
+    ('i', [0b00100, 0b00000, 0b01100, 0b00100, 0b00100, 0b00100, 0b01110, 0b00000]),
+    ('l', [0b01100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110, 0b00000]),
+    ('n', [0b00000, 0b00000, 0b10110, 0b11001, 0b10001, 0b10001, 0b10001, 0b00000]),
+    ('o', [0b00000, 0b00000, 0b01110, 0b10001, 0b10001, 0b10001, 0b01110, 0b00000]),
+    ('p', [0b00000, 0b00000, 0b11110, 0b10001, 0b10001, 0b11110, 0b10000, 0b10000]),
+    ('r', [0b00000, 0b00000, 0b10110, 0b11001, 0b10000, 0b10000, 0b10000, 0b00000]),
+    ('t', [0b01000, 0b01000, 0b11100, 0b01000, 0b01000, 0b01001, 0b00110, 0b00000]),
+    ('u', [0b00000, 0b00000, 0b10001, 0b10001, 0b10001, 0b10011, 0b01101, 0b00000]),
+    ('v', [0b00000, 0b00000, 0b10001, 0b10001, 0b10001, 0b01010, 0b00100, 0b00000]),
+    ('y', [0b00000, 0b00000, 0b10001, 0b10001, 0b10001, 0b01111, 0b00001, 0b01110]),
+    ('?', [0b01110, 0b10001, 0b00001, 0b00110, 0b00100, 0b00000, 0b00100, 0b00000]),
+];
+
+const GLYPH_WIDTH: usize = 5;
+/// Height of capital letters and digits, by which labels are centered.
+const GLYPH_HEIGHT: usize = 7;
+
+struct Canvas {
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>,
+}
+
+impl Canvas {
+    fn new(width: usize, height: usize, background: Rgb) -> Self {
+        let pixels = [background.0, background.1, background.2].repeat(width * height);
+        Canvas { width, height, pixels }
+    }
+
+    fn fill(&mut self, x: usize, y: usize, width: usize, height: usize, color: Rgb) {
+        for row in y..(y + height).min(self.height) {
+            for col in x..(x + width).min(self.width) {
+                let i = 3 * (row * self.width + col);
+                self.pixels[i..i + 3].copy_from_slice(&[color.0, color.1, color.2]);
+            }
+        }
+    }
+
+    /// Draws `text` centered on (`x`, `y`), each pixel of a glyph being `scale` pixels wide.
+    fn text(&mut self, text: &str, x: usize, y: usize, scale: usize, color: Rgb) {
+        let width = (text.chars().count() * (GLYPH_WIDTH + 1) - 1) * scale;
+        let left = x.saturating_sub(width / 2);
+        let top = y.saturating_sub(GLYPH_HEIGHT * scale / 2);
+
+        for (i, c) in text.chars().enumerate() {
+            let glyph = FONT.iter().find(|&&(glyph, _)| glyph == c).map_or(FONT[FONT.len() - 1].1, |&(_, rows)| rows);
+            for (row, bits) in glyph.iter().enumerate() {
+                for col in 0..GLYPH_WIDTH {
+                    if bits >> (GLYPH_WIDTH - 1 - col) & 1 == 1 {
+                        let px = left + (i * (GLYPH_WIDTH + 1) + col) * scale;
+                        self.fill(px, top + row * scale, scale, scale, color);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Draws `board` as a PNG image with squares `cell` pixels wide, colored and labelled as by [`super::svg::render`].
+pub fn render(board: &Board, cell: usize) -> Vec<u8> {
+    let cell = cell.max(8);
+    let margin = cell / 3;
+    let frame = board.frame();
+    let (width, height) = (2 * margin + frame.cols() * cell, 2 * margin + frame.rows() * cell);
+    let coordinate = |index: usize| margin + index * cell;
+
+    let mut canvas = Canvas::new(width, height, Rgb(0xff, 0xff, 0xff));
+
+    let scale = (cell / 24).max(1);
+    for square in frame.squares() {
+        let Some((row, col)) = frame.coordinates(square) else { continue; };
+        let (x, y) = (coordinate(col), coordinate(row));
+        let fill = square_color(board, square);
+        canvas.fill(x, y, cell, cell, fill);
+
+        let ink = match board.piece_at(square) {
+            Some(_) => fill.blend(INK_COLOR, 0.35),
+            None => INK_COLOR,
+        };
+        canvas.text(&square.to_string(), x + cell / 2, y + cell / 2, scale, ink);
+    }
+
+    let line = (cell / 20).max(2);
+    for ((row0, col0), (row1, col1)) in board.edges() {
+        let (x, y) = (coordinate(col0) - line / 2, coordinate(row0) - line / 2);
+        let (width, height) = ((col1 - col0) * cell + line, (row1 - row0) * cell + line);
+        canvas.fill(x, y, width, height, INK_COLOR);
+    }
+
+    let mut bytes = Vec::new();
+    let mut encoder = png::Encoder::new(&mut bytes, width as u32, height as u32);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().expect("writing to memory should not fail");
+    writer.write_image_data(&canvas.pixels).expect("image data should match its dimensions");
+    writer.finish().expect("writing to memory should not fail");
+
+    bytes
+}
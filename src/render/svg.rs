@@ -0,0 +1,53 @@
+use super::{square_color, INK_COLOR};
+
+use crate::board::Board;
+
+use std::fmt::Write;
+
+const CELL: usize = 60;
+const MARGIN: usize = 20;
+
+fn coordinate(index: usize) -> usize {
+    MARGIN + index * CELL
+}
+
+/// Draws `board` as an SVG document: the frame with its labels, each piece filled
+/// with its own color and outlined, and the uncovered date squares highlighted.
+///
+/// The image is sized so that squares are `cell_size` pixels wide.
+pub fn render(board: &Board, cell_size: usize) -> String {
+    let frame = board.frame();
+    let (width, height) = (2 * MARGIN + frame.cols() * CELL, 2 * MARGIN + frame.rows() * CELL);
+    let (pixel_width, pixel_height) = (width * cell_size / CELL, height * cell_size / CELL);
+    let ink = INK_COLOR.hex();
+    let mut svg = String::new();
+
+    writeln!(svg, r#"<svg xmlns="http://www.w3.org/2000/svg" width="{pixel_width}" height="{pixel_height}" viewBox="0 0 {width} {height}">"#).unwrap();
+
+    for square in frame.squares() {
+        let Some((row, col)) = frame.coordinates(square) else { continue; };
+        let (x, y) = (coordinate(col), coordinate(row));
+        writeln!(svg, r#"  <rect x="{x}" y="{y}" width="{CELL}" height="{CELL}" fill="{}"/>"#, square_color(board, square).hex()).unwrap();
+    }
+
+    writeln!(svg, r#"  <g font-family="sans-serif" font-size="{}" text-anchor="middle" dominant-baseline="central" fill="{ink}">"#, CELL * 3 / 10).unwrap();
+    for square in frame.squares() {
+        let Some((row, col)) = frame.coordinates(square) else { continue; };
+        let (x, y) = (coordinate(col) + CELL / 2, coordinate(row) + CELL / 2);
+        let style = match board.piece_at(square) {
+            Some(_) => r#" fill-opacity="0.35""#,
+            None => r#" font-weight="bold""#,
+        };
+        writeln!(svg, r#"    <text x="{x}" y="{y}"{style}>{square}</text>"#).unwrap();
+    }
+    writeln!(svg, "  </g>").unwrap();
+
+    let mut path = String::new();
+    for ((row0, col0), (row1, col1)) in board.edges() {
+        write!(path, "M{} {}L{} {}", coordinate(col0), coordinate(row0), coordinate(col1), coordinate(row1)).unwrap();
+    }
+    writeln!(svg, r#"  <path d="{path}" fill="none" stroke="{ink}" stroke-width="3" stroke-linecap="round"/>"#).unwrap();
+
+    writeln!(svg, "</svg>").unwrap();
+    svg
+}
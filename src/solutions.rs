@@ -1,87 +1,191 @@
-use crate::board::{Board, Piece, Path, Square, DateMap, Rotation, PlacementError};
+pub mod solver;
+pub mod apad;
+pub mod packed;
+
+use self::apad::{Header, IndexEntry};
+use self::solver::{Solver, Search, square_bit, square_bits};
+
+use crate::board::{Board, Frame, Piece, Square, Date, DateMap, DeadEnd, Placement, PlacementError};
 use crate::board::compact::CompactBoard;
 
 use std::thread;
-use std::sync::mpsc::{self, Sender, Receiver, SendError};
-use std::sync::Arc;
+use std::num::NonZeroUsize;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::collections::HashMap;
+use std::cmp::Reverse;
+use std::ops::{ControlFlow, Range};
 
+use std::fmt;
 use std::fs::File;
 use std::path::PathBuf;
 use std::io::{self, Write};
 
 use console::style;
-use indicatif::{ProgressBar, MultiProgress, ProgressStyle, ProgressIterator, ProgressFinish};
+use indicatif::{ProgressBar, MultiProgress, ProgressStyle};
 
 pub static SOLUTIONS: &[u8] = include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/solutions.apad"));
 
-pub fn generate(starting_board: Board, pieces: Vec<Piece>, progress: Option<&MultiProgress>) -> Result<Receiver<Board>, SendError<Board>> {
-    let (tx0, rx0) = mpsc::channel();
-    tx0.send(starting_board)?;
-    drop(tx0);
+/// Finds every solution of `frame`, grouped by date.
+///
+/// Arrangements leaving anything but a valid date uncovered are dropped as soon as they are found,
+/// and solutions are kept only as board records, so that frames with millions of solutions fit in memory.
+pub fn generate(frame: Frame, progress: Option<&MultiProgress>) -> SolutionIndex {
+    let solver = Solver::new(&frame.pieces(), frame);
+    let bar = progress.map(|mp| mp.add(make_spinner()));
 
-    let mut last_rx = rx0;
-    let mut last_piece = None;
+    let squares = frame.squares();
+    let mut gaps = vec![
+        square_bits(frame, squares.iter().copied().filter(Square::is_month)),
+        square_bits(frame, squares.iter().copied().filter(Square::is_day)),
+    ];
+    if frame.has_weekdays() {
+        gaps.push(square_bits(frame, squares.iter().copied().filter(Square::is_weekday)));
+    }
 
-    for piece in pieces {
-        let mut paths = Vec::<Path>::new();
+    let mut blocks = DateMap::<Vec<u8>>::new();
+    let mut uncovered = Vec::with_capacity(squares.len());
+    solver.solve(Board::new(frame).occupancy(), &gaps, |placements| {
+        if let Some(b) = bar.as_ref() { b.inc(1) }
 
-        let not_mirrored = Path::from(piece);
-        for rotation in Rotation::all_by_symmetry(piece.rotational_symmetry()) {
-            paths.push(not_mirrored.rotate(rotation));
-        }
-        if !piece.mirror_symmetric() {
-            let mirrored = Path::from(piece).mirror();
-            for rotation in Rotation::all_by_symmetry(piece.rotational_symmetry()) {
-                paths.push(mirrored.rotate(rotation))
-            }
+        let covered = placements.iter()
+            .filter_map(|placement| solver::placement_bits(placement, frame))
+            .fold(0, |bits, mask| bits | mask);
+        uncovered.clear();
+        uncovered.extend(squares.iter().copied().filter(|&square| covered & square_bit(frame, square) == 0));
+
+        if let Some(date) = Date::from_squares(&uncovered, frame.has_weekdays()) {
+            let compact = CompactBoard::from_placements(frame, placements).expect("solver should only place pieces of the frame");
+            blocks.entry(date).or_default().extend(compact.to_bytes());
         }
+    });
 
-        let (tx, rx) = mpsc::channel();
-        let mut forks = Vec::new();
-        for path in paths {
-            let tx_clone = tx.clone();
-            let (fork_tx, fork_rx): (Sender<Arc<Board>>, Receiver<Arc<Board>>) = mpsc::channel();
-            thread::spawn(move || {
-                for board in fork_rx.iter() {
-                    for start in Square::squares() {
-                        if let Some(new_board) = board.place(piece, start, &path) {
-                            tx_clone.send(new_board).unwrap();
-                        }
-                    }
-                }
-            });
-            forks.push(fork_tx);
+    if let Some(b) = bar { finish_spinner(b) }
+
+    SolutionIndex::from_blocks(frame, blocks)
+}
+
+pub fn solve_for(frame: Frame, date: Date) -> Receiver<Board> {
+    solve_from(Board::new(frame), date)
+}
+
+/// Finds every solution for `date` extending `board` with the pieces not yet placed on it.
+///
+/// The search runs on its own thread, which stops once the receiver is dropped and another solution is found.
+pub fn solve_from(board: Board, date: Date) -> Receiver<Board> {
+    let (tx, rx) = mpsc::channel();
+
+    thread::spawn(move || {
+        let date_squares = square_bits(board.frame(), date.squares());
+        if board.occupancy() & date_squares != 0 {
+            return;
         }
-        let bar = last_piece.and_then(|pc| progress.map(|mp| mp.add(make_spinner(pc))));
-        thread::spawn(move || {
-            for board in last_rx.iter() {
-                let board = Arc::new(board);
-                for fork_tx in forks.iter() {
-                    fork_tx.send(Arc::clone(&board)).unwrap();
-                }
-                if let Some(b) = bar.as_ref() { b.inc(1) }
+
+        let pieces = board.frame().pieces().into_iter()
+            .filter(|&piece| !board.contains(piece))
+            .collect::<Vec<_>>();
+        let solver = Solver::new(&pieces, board.frame());
+        solver.solve_until(board.occupancy() | date_squares, &[], |placements| {
+            match tx.send(place_all(&board, placements)) {
+                Ok(()) => ControlFlow::Continue(()),
+                Err(_) => ControlFlow::Break(()),
             }
-            if let Some(b) = bar { finish_spinner(b) }
         });
-        last_rx = rx;
-        last_piece = Some(piece);
-    }
+    });
 
-    let (txn, rxn) = mpsc::channel();
-    let bar = progress.map(|mp| mp.add(make_spinner(last_piece.unwrap())));
-    thread::spawn(move || {
-        for board in last_rx.iter() {
-            txn.send(board).unwrap();
-            if let Some(b) = bar.as_ref() { b.inc(1) }
+    rx
+}
+
+/// Number of solutions for each of `dates`, solving for several dates at once.
+pub fn count_for(frame: Frame, dates: &[Date], progress: Option<&MultiProgress>) -> Vec<usize> {
+    let solver = Solver::new(&frame.pieces(), frame);
+    let occupancy = Board::new(frame).occupancy();
+    let bar = progress.map(|mp| mp.add(make_progress_bar(dates.len())));
+
+    let next = AtomicUsize::new(0);
+    let counts = Mutex::new(vec![0; dates.len()]);
+    let threads = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some(date) = dates.get(i) else { break };
+                let mut count = 0;
+                solver.solve(occupancy | square_bits(frame, date.squares()), &[], |_| count += 1);
+                counts.lock().unwrap()[i] = count;
+                if let Some(b) = bar.as_ref() { b.inc(1) }
+            });
         }
-        if let Some(b) = bar { finish_spinner(b) }
     });
 
-    Ok(rxn)
+    if let Some(b) = bar {
+        b.finish();
+        b.set_style(b.style()
+            .template("{spinner:.green} {msg} :{human_pos:>10.bold.green} solved ({elapsed:.dim})")
+            .unwrap()
+        );
+        b.tick();
+    }
+
+    counts.into_inner().unwrap()
 }
 
-fn make_spinner(piece: Piece) -> ProgressBar {
-    let bar = ProgressBar::new_spinner().with_message(format!("{piece}"));
+/// Whether `board` can be completed to a solution for `date`, giving an example solution if one is found
+/// after trying at most `limit` placements, or `None` if the search gives up before finding either.
+///
+/// The sizes of the empty regions are checked first, as by [`Board::dead_end`].
+pub fn completion(board: &Board, date: Date, limit: usize) -> Result<Option<Board>, DeadEnd> {
+    if let Some(dead_end) = board.dead_end(date) {
+        return Err(dead_end);
+    }
+
+    let pieces = board.frame().pieces().into_iter()
+        .filter(|&piece| !board.contains(piece))
+        .collect::<Vec<_>>();
+    let date_squares = square_bits(board.frame(), date.squares());
+    match Solver::new(&pieces, board.frame()).find(board.occupancy() | date_squares, &[], limit) {
+        Search::Found(placements) => Ok(Some(place_all(board, &placements))),
+        Search::NoSolution => Err(DeadEnd::NoSolution),
+        Search::GaveUp => Ok(None),
+    }
+}
+
+/// Places `count` pieces of a solution among `boards`, each piece chosen as the placement
+/// shared by the most solutions agreeing with the pieces placed before it.
+pub fn hint(boards: &[Board], count: usize) -> Board {
+    let mut candidates = boards.iter()
+        .filter_map(|board| board.placements().ok())
+        .collect::<Vec<_>>();
+    let mut revealed = Vec::<Placement>::new();
+
+    while revealed.len() < count {
+        let mut frequency = HashMap::<Placement, usize>::new();
+        for placements in candidates.iter() {
+            for &placement in placements.iter().filter(|&placement| !revealed.contains(placement)) {
+                *frequency.entry(placement).or_default() += 1;
+            }
+        }
+
+        let best = frequency.into_iter().max_by_key(|&(placement, n)| (n, Reverse(placement)));
+        let Some((placement, _)) = best else { break; };
+
+        candidates.retain(|placements| placements.contains(&placement));
+        revealed.push(placement);
+    }
+
+    let frame = boards.first().map_or(Frame::default(), Board::frame);
+    place_all(&Board::new(frame), &revealed)
+}
+
+fn place_all(board: &Board, placements: &[Placement]) -> Board {
+    placements.iter().fold(board.clone(), |board, placement| {
+        board.apply(placement).expect("solver should only produce valid placements")
+    })
+}
+
+fn make_spinner() -> ProgressBar {
+    let bar = ProgressBar::new_spinner().with_message("Arrangements");
     bar.set_style(
         ProgressStyle::with_template("{spinner:.blue} {msg} :{human_pos:>10.bold.dim} found ({elapsed:.dim})")
             .unwrap().tick_chars("⠇⡆⣄⣠⢰⠸⠙⠋⣿")
@@ -89,6 +193,15 @@ fn make_spinner(piece: Piece) -> ProgressBar {
     bar
 }
 
+fn make_progress_bar(len: usize) -> ProgressBar {
+    let bar = ProgressBar::new(len as u64).with_message("Dates");
+    bar.set_style(
+        ProgressStyle::with_template("{spinner:.blue} {msg} :{human_pos:>10.bold.dim} of {human_len} solved ({elapsed:.dim})")
+            .unwrap().tick_chars("⠇⡆⣄⣠⢰⠸⠙⠋⣿")
+    );
+    bar
+}
+
 fn finish_spinner(bar: ProgressBar) {
     bar.finish();
     bar.set_style(bar.style()
@@ -98,9 +211,38 @@ fn finish_spinner(bar: ProgressBar) {
     bar.tick();
 }
 
+#[derive(Debug)]
 pub enum DataError {
     BoardError,
     IoError(io::ErrorKind),
+    Truncated,
+    TrailingData,
+    UnsupportedVersion(u8),
+    UnknownFrame(u8),
+    InvalidFrame(String),
+    UnpackablePiece(Piece),
+    UnsupportedFlags(u8),
+    ChecksumMismatch { expected: u32, found: u32 },
+    InvalidIndex,
+}
+
+impl fmt::Display for DataError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DataError::BoardError => write!(f, "board does not consist of valid placements"),
+            DataError::IoError(kind) => write!(f, "{kind}"),
+            DataError::Truncated => write!(f, "file is truncated"),
+            DataError::TrailingData => write!(f, "file has data past the boards it declares"),
+            DataError::UnsupportedVersion(version) => write!(f, "unsupported APAD version {version}"),
+            DataError::UnknownFrame(frame) => write!(f, "unknown frame identifier {frame}"),
+            DataError::InvalidFrame(message) => write!(f, "invalid frame definition: {message}"),
+            DataError::UnpackablePiece(piece) => write!(f, "{piece} has too many placements to be packed"),
+            DataError::UnsupportedFlags(flags) => write!(f, "unsupported flags {flags:#010b}"),
+            DataError::ChecksumMismatch { expected, found } =>
+                write!(f, "checksum {found:08x} does not match expected {expected:08x}"),
+            DataError::InvalidIndex => write!(f, "date index does not match boards"),
+        }
+    }
 }
 
 impl From<PlacementError> for DataError {
@@ -115,9 +257,19 @@ impl From<io::Error> for DataError {
     }
 }
 
-pub fn write_boards(boards: Vec<Board>, file: PathBuf) -> Result<(), DataError> {
-    let mut file_handle = File::create(file.clone())?;
+/// Writes `boards` to `file` in the APAD format, compressed if `compress` is set and every board is a solution.
+pub fn write_boards(boards: Vec<Board>, file: PathBuf, compress: bool) -> Result<(), DataError> {
+    let count = boards.len();
+    write_file(file, count, |bar| encode_boards(boards, compress, Some(bar)))
+}
+
+/// Writes `solutions` to `file` in the APAD format, packing each date's boards if `compress` is set.
+pub fn write_index(solutions: &SolutionIndex, file: PathBuf, compress: bool) -> Result<(), DataError> {
+    write_file(file, solutions.total(), |bar| encode_index(solutions, compress, Some(bar)))
+}
 
+/// Writes the bytes given by `encode` to `file`, showing its progress through `count` boards.
+fn write_file(file: PathBuf, count: usize, encode: impl FnOnce(&ProgressBar) -> Result<Vec<u8>, DataError>) -> Result<(), DataError> {
     println!(
         "{} Writing to file {}",
         style("⣿").blue(),
@@ -129,27 +281,225 @@ pub fn write_boards(boards: Vec<Board>, file: PathBuf) -> Result<(), DataError>
     ).as_str()).unwrap()
         .tick_chars("⠇⡆⣄⣠⢰⠸⠙⠋⣿")
         .progress_chars("⠶⠶⠆ ");
+    let bar = ProgressBar::new(count as u64).with_style(style);
 
-    for board in boards.iter().progress_with_style(style).with_finish(ProgressFinish::Abandon) {
-        let bytes = CompactBoard::try_from(board.to_owned())?.to_bytes();
-        file_handle.write_all(&bytes)?;
-    }
+    let bytes = encode(&bar)?;
+    bar.abandon();
+
+    // The file is only created once encoding succeeds, so that a failure leaves no empty file behind.
+    File::create(file)?.write_all(&bytes)?;
 
     Ok(())
 }
 
+/// Encodes `boards` as an APAD file.
+///
+/// If every board is a solution, the boards are grouped by date and the file is indexed,
+/// each date's boards being packed if `compress` is set.
+pub fn encode_boards(boards: Vec<Board>, compress: bool, progress: Option<&ProgressBar>) -> Result<Vec<u8>, DataError> {
+    let inc = |n: usize| if let Some(bar) = progress { bar.inc(n as u64) };
+
+    let frame = boards.first().map_or(Frame::default(), Board::frame);
+    if boards.iter().any(|board| board.frame() != frame) {
+        return Err(DataError::BoardError);
+    }
+
+    let Some(blocks) = date_blocks(&boards) else {
+        let mut records = Vec::with_capacity(boards.len() * apad::record_size(frame));
+        for board in boards {
+            records.extend(CompactBoard::try_from(board)?.to_bytes());
+            inc(1);
+        }
+        return Ok(apad::encode(frame, None, false, &records));
+    };
+
+    let tables = if compress { Some(packed::Tables::new(frame)?) } else { None };
+    let mut entries = Vec::with_capacity(blocks.len());
+    let mut records = Vec::with_capacity(boards.len() * apad::record_size(frame));
+    for (date, block) in blocks {
+        entries.push(IndexEntry { date, offset: records.len() as u32, count: block.len() as u32 });
+        if let Some(tables) = tables.as_ref() {
+            records.extend(packed::pack(tables, date, &block)?);
+        } else {
+            for board in block.iter() {
+                records.extend(CompactBoard::try_from(board.to_owned())?.to_bytes());
+            }
+        }
+        inc(block.len());
+    }
+
+    Ok(apad::encode(frame, Some(&entries), compress, &records))
+}
+
+/// Encodes `solutions` as an indexed APAD file, packing each date's boards if `compress` is set.
+pub fn encode_index(solutions: &SolutionIndex, compress: bool, progress: Option<&ProgressBar>) -> Result<Vec<u8>, DataError> {
+    let frame = solutions.frame;
+    let tables = if compress { Some(packed::Tables::new(frame)?) } else { None };
+
+    let mut dates = solutions.dates().collect::<Vec<_>>();
+    dates.sort_unstable();
+
+    let mut entries = Vec::with_capacity(dates.len());
+    let mut records = Vec::new();
+    for date in dates {
+        let (entry, range) = &solutions.entries[&date];
+        entries.push(IndexEntry { date, offset: records.len() as u32, count: entry.count });
+        match (tables.as_ref(), solutions.tables.as_ref()) {
+            (Some(tables), _) => records.extend(packed::pack(tables, date, &solutions.get(&date)?)?),
+            (None, None) => records.extend(&solutions.records[range.clone()]),
+            (None, Some(_)) => {
+                for board in solutions.get(&date)? {
+                    records.extend(CompactBoard::try_from(board)?.to_bytes());
+                }
+            }
+        }
+        if let Some(bar) = progress { bar.inc(entry.count as u64) }
+    }
+
+    Ok(apad::encode(frame, Some(&entries), compress, &records))
+}
+
+/// Groups `boards` by the date they solve, in order of date, if every board is a solution.
+fn date_blocks(boards: &[Board]) -> Option<Vec<(Date, Vec<Board>)>> {
+    let mut solutions = boards.iter()
+        .map(|board| board.solved_for().map(|date| (date, board)))
+        .collect::<Option<Vec<_>>>()?;
+    solutions.sort_by_key(|&(date, _)| date);
+
+    let mut blocks = Vec::<(Date, Vec<Board>)>::new();
+    for (date, board) in solutions {
+        match blocks.last_mut() {
+            Some((block_date, block)) if *block_date == date => block.push(board.clone()),
+            _ => blocks.push((date, vec![board.clone()])),
+        }
+    }
+    Some(blocks)
+}
+
+/// Checks that `bytes` form an intact APAD file without decoding any boards.
+pub fn verify(bytes: &[u8]) -> Result<Header, DataError> {
+    apad::split(bytes).map(|contents| contents.header)
+}
+
+/// Frame of the boards of an APAD file, once the file is checked as by [`verify`].
+pub fn frame(bytes: &[u8]) -> Result<Frame, DataError> {
+    apad::split(bytes).map(|contents| contents.frame)
+}
+
 pub fn read_boards(bytes: &[u8]) -> Result<Vec<Board>, DataError> {
+    let contents = apad::split(bytes)?;
+    match contents.index.as_deref() {
+        Some(entries) if contents.compressed() => {
+            let ranges = apad::ranges(entries, contents.records.len(), true, apad::record_size(contents.frame));
+            let tables = packed::Tables::new(contents.frame)?;
+            let mut boards = Vec::with_capacity((contents.header.count as usize).min(contents.records.len()));
+            for (entry, range) in entries.iter().zip(ranges) {
+                boards.extend(packed::unpack(&tables, entry.date, &contents.records[range], entry.count as usize)?);
+            }
+            Ok(boards)
+        }
+        _ => decode(contents.frame, contents.records),
+    }
+}
+
+fn decode(frame: Frame, records: &[u8]) -> Result<Vec<Board>, DataError> {
     let mut boards = Vec::new();
 
-    for chunk in bytes.chunks_exact(9) {
-        let chunk: [u8; 9] = chunk.try_into().expect("chunk size should be exactly 9");
-        let compact = CompactBoard::from(chunk);
+    for chunk in records.chunks_exact(apad::record_size(frame)) {
+        let compact = CompactBoard::from_bytes(frame, chunk);
         boards.push(Board::try_from(compact)?);
     }
 
     Ok(boards)
 }
 
+/// Solutions grouped by date, decoded only when a date's solutions are asked for.
+#[derive(Debug)]
+pub struct SolutionIndex {
+    frame: Frame,
+    records: Vec<u8>,
+    /// Tables the records are packed with, if they are
+    tables: Option<packed::Tables>,
+    entries: DateMap<(IndexEntry, Range<usize>)>,
+}
+
+impl SolutionIndex {
+    /// Reads the index of an APAD file, decoding every board only if the file has no index.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DataError> {
+        let contents = apad::split(bytes)?;
+        match contents.index.as_deref() {
+            Some(entries) => {
+                let ranges = apad::ranges(entries, contents.records.len(), contents.compressed(), apad::record_size(contents.frame));
+                let tables = if contents.compressed() { Some(packed::Tables::new(contents.frame)?) } else { None };
+                Ok(SolutionIndex {
+                    frame: contents.frame,
+                    records: contents.records.to_vec(),
+                    tables,
+                    entries: entries.iter().zip(ranges).map(|(&entry, range)| (entry.date, (entry, range))).collect(),
+                })
+            }
+            None => Ok(Self::from_boards(decode(contents.frame, contents.records)?)),
+        }
+    }
+
+    /// Indexes the solutions among `boards`, discarding the rest.
+    pub fn from_boards(boards: Vec<Board>) -> Self {
+        let solutions = boards.into_iter().filter(|board| board.solved_for().is_some()).collect();
+        let bytes = encode_boards(solutions, false, None).expect("solutions should consist of valid placements");
+        Self::from_bytes(&bytes).expect("encoded solutions should be readable")
+    }
+
+    /// Indexes board records of `frame` grouped by date, sorting the records of each date.
+    fn from_blocks(frame: Frame, blocks: DateMap<Vec<u8>>) -> Self {
+        let size = apad::record_size(frame);
+        let mut blocks = blocks.into_iter().collect::<Vec<_>>();
+        blocks.sort_unstable_by_key(|&(date, _)| date);
+
+        let mut records = Vec::with_capacity(blocks.iter().map(|(_, block)| block.len()).sum());
+        let mut entries = DateMap::new();
+        for (date, block) in blocks {
+            let mut chunks = block.chunks_exact(size).collect::<Vec<_>>();
+            chunks.sort_unstable();
+
+            let start = records.len();
+            for chunk in chunks.iter() {
+                records.extend_from_slice(chunk);
+            }
+            let entry = IndexEntry { date, offset: start as u32, count: chunks.len() as u32 };
+            entries.insert(date, (entry, start..records.len()));
+        }
+
+        SolutionIndex { frame, records, tables: None, entries }
+    }
+
+    pub fn frame(&self) -> Frame {
+        self.frame
+    }
+
+    /// Number of solutions over every date.
+    pub fn total(&self) -> usize {
+        self.entries.values().map(|(entry, _)| entry.count as usize).sum()
+    }
+
+    pub fn dates(&self) -> impl Iterator<Item = Date> + '_ {
+        self.entries.keys().copied()
+    }
+
+    pub fn count(&self, date: &Date) -> usize {
+        self.entries.get(date).map_or(0, |(entry, _)| entry.count as usize)
+    }
+
+    pub fn get(&self, date: &Date) -> Result<Vec<Board>, DataError> {
+        match self.entries.get(date) {
+            None => Ok(Vec::new()),
+            Some((entry, range)) => match self.tables.as_ref() {
+                Some(tables) => packed::unpack(tables, entry.date, &self.records[range.clone()], entry.count as usize),
+                None => decode(self.frame, &self.records[range.clone()]),
+            },
+        }
+    }
+}
+
 pub fn classify(boards: Vec<Board>) -> DateMap<Vec<Board>> {
     let mut solutions = DateMap::<Vec<Board>>::new();
     for board in boards {
@@ -0,0 +1,322 @@
+use super::DataError;
+
+use crate::board::{Date, Frame, FrameError, Square};
+use crate::board::compact::CompactBoard;
+
+use std::ops::Range;
+
+pub const MAGIC: &[u8; 4] = b"APAD";
+pub const VERSION: u8 = 2;
+pub const HEADER_SIZE: usize = 16;
+
+/// Identifier of the classic frame together with the eight original pieces.
+pub const CLASSIC: u8 = 0;
+/// Identifier of the weekday frame together with its ten pieces.
+pub const WEEKDAY: u8 = 1;
+/// Identifier of any other frame, whose definition is stored ahead of the index.
+pub const CUSTOM: u8 = 2;
+
+/// Flag set when the boards are grouped by date and preceded by a date index.
+pub const INDEXED: u8 = 0b0000_0001;
+
+/// Flag set when each date's boards are packed rather than stored as fixed-size records,
+/// which requires the file to be indexed.
+pub const COMPRESSED: u8 = 0b0000_0010;
+
+/// Flags understood by this version of the reader.
+pub const KNOWN_FLAGS: u8 = INDEXED | COMPRESSED;
+
+pub fn frame_id(frame: Frame) -> u8 {
+    if frame == Frame::classic() {
+        CLASSIC
+    } else if frame == Frame::weekday() {
+        WEEKDAY
+    } else {
+        CUSTOM
+    }
+}
+
+/// Frame identified by `id`, other than a custom frame.
+pub fn frame(id: u8) -> Result<Frame, DataError> {
+    match id {
+        CLASSIC => Ok(Frame::classic()),
+        WEEKDAY => Ok(Frame::weekday()),
+        _ => Err(DataError::UnknownFrame(id)),
+    }
+}
+
+/// Definition of a custom frame as stored: its length in bytes followed by its text.
+fn definition(frame: Frame) -> Vec<u8> {
+    let text = frame.to_string();
+    let mut bytes = (text.len() as u16).to_be_bytes().to_vec();
+    bytes.extend(text.as_bytes());
+    bytes
+}
+
+/// Reads the definition of a custom frame at the start of `payload`, returning the frame and the length of the definition.
+fn read_definition(payload: &[u8]) -> Result<(Frame, usize), DataError> {
+    let len = payload.get(0..2).ok_or(DataError::Truncated)?;
+    let len = 2 + u16::from_be_bytes(len.try_into().unwrap()) as usize;
+    let text = std::str::from_utf8(payload.get(2..len).ok_or(DataError::Truncated)?)
+        .map_err(|err| DataError::InvalidFrame(err.to_string()))?;
+    let frame = text.parse().map_err(|err: FrameError| DataError::InvalidFrame(err.to_string()))?;
+    Ok((frame, len))
+}
+
+/// Size of a board record of `frame`, when not packed.
+pub fn record_size(frame: Frame) -> usize {
+    CompactBoard::size(frame)
+}
+
+/// Size of an index entry of `frame`, which has a byte for the weekday of each date if the frame has weekdays.
+pub fn index_entry_size(frame: Frame) -> usize {
+    if frame.has_weekdays() { 11 } else { 10 }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Header {
+    pub version: u8,
+    pub frame: u8,
+    pub flags: u8,
+    pub count: u32,
+    pub checksum: u32,
+}
+
+impl Header {
+    pub fn to_bytes(&self) -> [u8; HEADER_SIZE] {
+        let mut bytes = [0; HEADER_SIZE];
+        bytes[0..4].copy_from_slice(MAGIC);
+        bytes[4] = self.version;
+        bytes[5] = self.frame;
+        bytes[6] = self.flags;
+        bytes[8..12].copy_from_slice(&self.count.to_be_bytes());
+        bytes[12..16].copy_from_slice(&self.checksum.to_be_bytes());
+        bytes
+    }
+}
+
+/// Location of the boards solved for `date`, `offset` being in bytes from the first record.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IndexEntry {
+    pub date: Date,
+    pub offset: u32,
+    pub count: u32,
+}
+
+impl IndexEntry {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![self.date.month as u8, self.date.day as u8];
+        bytes.extend(self.date.weekday.map(|weekday| weekday as u8));
+        bytes.extend(self.offset.to_be_bytes());
+        bytes.extend(self.count.to_be_bytes());
+        bytes
+    }
+
+    fn from_bytes(frame: Frame, bytes: &[u8]) -> Result<Self, DataError> {
+        let square = |byte: u8| Square::try_from(byte).map_err(|_| DataError::InvalidIndex);
+        let (weekday, rest) = match frame.has_weekdays() {
+            true => (Some(square(bytes[2])?), &bytes[3..]),
+            false => (None, &bytes[2..]),
+        };
+        let date = Date { month: square(bytes[0])?, day: square(bytes[1])?, weekday };
+        if !date.is_valid() {
+            return Err(DataError::InvalidIndex);
+        }
+
+        Ok(IndexEntry {
+            date,
+            offset: u32::from_be_bytes(rest[0..4].try_into().unwrap()),
+            count: u32::from_be_bytes(rest[4..8].try_into().unwrap()),
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct Contents<'a> {
+    pub header: Header,
+    pub frame: Frame,
+    pub index: Option<Vec<IndexEntry>>,
+    pub records: &'a [u8],
+}
+
+impl Contents<'_> {
+    pub fn compressed(&self) -> bool {
+        self.header.flags & COMPRESSED != 0
+    }
+}
+
+/// Byte ranges within the records of each entry of an index.
+///
+/// Packed boards vary in size, so each date's boards are taken to extend to the next entry's offset.
+pub fn ranges(entries: &[IndexEntry], records_len: usize, compressed: bool, record_size: usize) -> Vec<Range<usize>> {
+    entries.iter().enumerate().map(|(i, entry)| {
+        let start = entry.offset as usize;
+        let end = if compressed {
+            entries.get(i + 1).map_or(records_len, |next| next.offset as usize)
+        } else {
+            start + entry.count as usize * record_size
+        };
+        start..end
+    }).collect()
+}
+
+/// Assembles an APAD file from board records, along with an index if the records are grouped by date.
+///
+/// Compressed records must be indexed, as their number cannot be told from their size.
+pub fn encode(frame: Frame, index: Option<&[IndexEntry]>, compressed: bool, records: &[u8]) -> Vec<u8> {
+    assert!(index.is_some() || !compressed, "compressed records should be indexed");
+
+    let mut payload = Vec::new();
+    if frame_id(frame) == CUSTOM {
+        payload.extend(definition(frame));
+    }
+    if let Some(entries) = index {
+        payload.extend((entries.len() as u16).to_be_bytes());
+        for entry in entries {
+            payload.extend(entry.to_bytes());
+        }
+    }
+    payload.extend(records);
+
+    let count = match index {
+        Some(entries) => entries.iter().map(|entry| entry.count).sum(),
+        None => (records.len() / record_size(frame)) as u32,
+    };
+
+    let mut flags = 0;
+    if index.is_some() { flags |= INDEXED; }
+    if compressed { flags |= COMPRESSED; }
+
+    let header = Header {
+        version: VERSION,
+        frame: frame_id(frame),
+        flags,
+        count,
+        checksum: crc32(&payload),
+    };
+
+    let mut bytes = header.to_bytes().to_vec();
+    bytes.extend(payload);
+    bytes
+}
+
+/// Checks the container of an APAD file, returning its header, index and the board records within.
+///
+/// Files without the magic number are taken to be headerless version 1 files.
+pub fn split(bytes: &[u8]) -> Result<Contents<'_>, DataError> {
+    if !bytes.starts_with(MAGIC) {
+        let record_size = record_size(Frame::classic());
+        if !bytes.len().is_multiple_of(record_size) {
+            return Err(DataError::Truncated);
+        }
+        let header = Header {
+            version: 1,
+            frame: CLASSIC,
+            flags: 0,
+            count: (bytes.len() / record_size) as u32,
+            checksum: crc32(bytes),
+        };
+        return Ok(Contents { header, frame: Frame::classic(), index: None, records: bytes });
+    }
+
+    if bytes.len() < HEADER_SIZE {
+        return Err(DataError::Truncated);
+    }
+
+    let header = Header {
+        version: bytes[4],
+        frame: bytes[5],
+        flags: bytes[6],
+        count: u32::from_be_bytes(bytes[8..12].try_into().unwrap()),
+        checksum: u32::from_be_bytes(bytes[12..16].try_into().unwrap()),
+    };
+
+    if header.version != VERSION {
+        return Err(DataError::UnsupportedVersion(header.version));
+    }
+    let compressed = header.flags & COMPRESSED != 0;
+    if header.flags & !KNOWN_FLAGS != 0 || (compressed && header.flags & INDEXED == 0) {
+        return Err(DataError::UnsupportedFlags(header.flags));
+    }
+
+    let payload = &bytes[HEADER_SIZE..];
+
+    let (frame, definition_len) = match header.frame {
+        CUSTOM => read_definition(payload)?,
+        id => (frame(id)?, 0),
+    };
+    let (record_size, entry_size) = (record_size(frame), index_entry_size(frame));
+
+    let index_len = if header.flags & INDEXED != 0 {
+        let entry_count = payload.get(definition_len..definition_len + 2).ok_or(DataError::Truncated)?;
+        2 + u16::from_be_bytes(entry_count.try_into().unwrap()) as usize * entry_size
+    } else {
+        0
+    };
+
+    let expected_len = definition_len + index_len + if compressed { 0 } else { header.count as usize * record_size };
+    if payload.len() < expected_len {
+        return Err(DataError::Truncated);
+    }
+    if payload.len() > expected_len && !compressed {
+        return Err(DataError::TrailingData);
+    }
+
+    let checksum = crc32(payload);
+    if checksum != header.checksum {
+        return Err(DataError::ChecksumMismatch { expected: header.checksum, found: checksum });
+    }
+
+    let records = &payload[definition_len + index_len..];
+
+    let index = if header.flags & INDEXED != 0 {
+        let entries = payload[definition_len + 2..definition_len + index_len].chunks_exact(entry_size)
+            .map(|bytes| IndexEntry::from_bytes(frame, bytes))
+            .collect::<Result<Vec<_>, _>>()?;
+        let ranges = ranges(&entries, records.len(), compressed, record_size);
+        let aligned = |range: &Range<usize>| compressed || range.start.is_multiple_of(record_size);
+        if ranges.iter().any(|range| !aligned(range) || range.start > range.end || range.end > records.len()) {
+            return Err(DataError::InvalidIndex);
+        }
+        // Each date's boards follow on from the last's, together making up every record.
+        let contiguous = ranges.windows(2).all(|pair| pair[0].end == pair[1].start);
+        let covering = match (ranges.first(), ranges.last()) {
+            (Some(first), Some(last)) => first.start == 0 && last.end == records.len(),
+            _ => records.is_empty(),
+        };
+        if !contiguous || !covering {
+            return Err(DataError::InvalidIndex);
+        }
+        if entries.iter().map(|entry| entry.count as u64).sum::<u64>() != header.count as u64 {
+            return Err(DataError::InvalidIndex);
+        }
+        Some(entries)
+    } else {
+        None
+    };
+
+    Ok(Contents { header, frame, index, records })
+}
+
+const CRC_TABLE: [u32; 256] = {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+/// CRC-32 (as used by zlib and PNG) of `bytes`.
+pub fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!0, |crc, &byte| {
+        CRC_TABLE[((crc ^ byte as u32) & 0xFF) as usize] ^ (crc >> 8)
+    })
+}
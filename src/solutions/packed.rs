@@ -0,0 +1,141 @@
+use super::DataError;
+use super::solver::{self, Bits, square_bits};
+
+use crate::board::{Board, Date, Frame, Piece, Placement};
+
+use std::collections::HashMap;
+
+/// Order in which placements are stored, chosen so that sorted solutions share long prefixes.
+///
+/// The last piece is not stored, as it covers exactly the squares the others leave empty.
+fn order(frame: Frame) -> Vec<Piece> {
+    if frame == Frame::classic() {
+        vec![Piece::U, Piece::Y, Piece::O, Piece::V, Piece::P, Piece::L, Piece::N, Piece::Z]
+    } else if frame == Frame::weekday() {
+        vec![Piece::U, Piece::Y, Piece::V, Piece::P, Piece::L, Piece::N, Piece::Z, Piece::J, Piece::S, Piece::I]
+    } else {
+        frame.pieces()
+    }
+}
+
+/// Numbers every placement of each piece, so that a placement is stored in a single byte.
+///
+/// Building the tables means finding every placement of every piece, so they are built once
+/// for each frame and shared by every date packed or unpacked.
+#[derive(Debug)]
+pub struct Tables {
+    frame: Frame,
+    order: Vec<Piece>,
+    placements: Vec<Vec<(Placement, Bits)>>,
+    numbers: Vec<HashMap<Bits, u8>>,
+    /// Placement of the last piece covering exactly the given squares
+    last: HashMap<Bits, Placement>,
+}
+
+impl Tables {
+    /// Tables for `frame`, as long as every stored piece has few enough placements to be numbered by a single byte.
+    pub fn new(frame: Frame) -> Result<Self, DataError> {
+        let order = order(frame);
+        let placements = order.iter().map(|&piece| solver::placements(piece, frame)).collect::<Vec<_>>();
+        if let Some(i) = placements[..order.len() - 1].iter().position(|table| table.len() > 256) {
+            return Err(DataError::UnpackablePiece(order[i]));
+        }
+        let numbers = placements.iter()
+            .map(|table| table.iter().enumerate().map(|(i, &(_, bits))| (bits, i as u8)).collect())
+            .collect();
+        let last = solver::placements(order[order.len() - 1], frame).into_iter()
+            .map(|(placement, bits)| (bits, placement))
+            .collect();
+        Ok(Tables { frame, order, placements, numbers, last })
+    }
+
+    pub fn frame(&self) -> Frame {
+        self.frame
+    }
+
+    /// Number of pieces stored, every one but the last.
+    fn stored(&self) -> usize {
+        self.order.len() - 1
+    }
+}
+
+/// Encodes solutions for `date`, sorted so that each shares a prefix of its placements with the one before it.
+///
+/// Each solution is stored as the number of leading placements shared with the previous solution,
+/// followed by a byte numbering each of the remaining stored placements.
+pub fn pack(tables: &Tables, date: Date, boards: &[Board]) -> Result<Vec<u8>, DataError> {
+    let frame = tables.frame;
+    let stored = tables.stored();
+
+    let mut keys = Vec::with_capacity(boards.len());
+    for board in boards {
+        if board.frame() != frame || board.solved_for() != Some(date) {
+            return Err(DataError::BoardError);
+        }
+        let placements = board.placements()?;
+        let mut key = vec![0; stored];
+        for (i, &piece) in tables.order[..stored].iter().enumerate() {
+            let bits = placements.iter()
+                .find(|placement| placement.piece == piece)
+                .and_then(|placement| solver::placement_bits(placement, frame))
+                .ok_or(DataError::BoardError)?;
+            key[i] = tables.numbers[i][&bits];
+        }
+        keys.push(key);
+    }
+    keys.sort_unstable();
+
+    let mut bytes = Vec::new();
+    let mut previous: Option<Vec<u8>> = None;
+    for key in keys {
+        let shared = previous.as_ref().map_or(0, |previous| {
+            previous.iter().zip(key.iter()).take_while(|(a, b)| a == b).count()
+        });
+        bytes.push(shared as u8);
+        bytes.extend(&key[shared..]);
+        previous = Some(key);
+    }
+
+    Ok(bytes)
+}
+
+/// Decodes `count` solutions for `date` encoded by [`pack`] with the same tables, which must make up all of `bytes`.
+pub fn unpack(tables: &Tables, date: Date, bytes: &[u8], count: usize) -> Result<Vec<Board>, DataError> {
+    let frame = tables.frame;
+    let stored = tables.stored();
+    let uncovered = Board::new(frame).occupancy() | square_bits(frame, date.squares());
+
+    // Every solution takes at least a byte, whatever count the file gives.
+    let mut boards = Vec::with_capacity(count.min(bytes.len()));
+    let mut key = vec![0; stored];
+    let mut rest = bytes;
+    for i in 0..count {
+        let (&shared, tail) = rest.split_first().ok_or(DataError::Truncated)?;
+        let shared = shared as usize;
+        if shared > stored || (i == 0 && shared != 0) {
+            return Err(DataError::BoardError);
+        }
+        let (numbers, tail) = tail.split_at_checked(stored - shared).ok_or(DataError::Truncated)?;
+        key[shared..].copy_from_slice(numbers);
+        rest = tail;
+
+        let mut board = Board::new(frame);
+        let mut covered = uncovered;
+        for (j, &number) in key.iter().enumerate() {
+            let &(placement, bits) = tables.placements[j].get(number as usize).ok_or(DataError::BoardError)?;
+            if covered & bits != 0 {
+                return Err(DataError::BoardError);
+            }
+            board = board.apply(&placement).ok_or(DataError::BoardError)?;
+            covered |= bits;
+        }
+        let placement = tables.last.get(&!covered).ok_or(DataError::BoardError)?;
+        boards.push(board.apply(placement).ok_or(DataError::BoardError)?);
+    }
+
+    if !rest.is_empty() {
+        return Err(DataError::TrailingData);
+    }
+
+    Ok(boards)
+}
@@ -0,0 +1,222 @@
+use crate::board::{Frame, Piece, Path, Square, Placement};
+
+use std::ops::ControlFlow;
+
+/// Set of cells of the grid of a frame, bit `i` standing for cell `i` as numbered by [`Frame::position`].
+pub type Bits = u64;
+
+/// Cell of `square` within `frame`, or no cell if it is not in the frame.
+pub fn square_bit(frame: Frame, square: Square) -> Bits {
+    frame.position(square).map_or(0, |position| 1 << position)
+}
+
+pub fn square_bits(frame: Frame, squares: impl IntoIterator<Item = Square>) -> Bits {
+    squares.into_iter().fold(0, |bits, square| bits | square_bit(frame, square))
+}
+
+/// Shape of the grid of a frame, for finding the cells next to others.
+#[derive(Debug)]
+struct Grid {
+    cols: usize,
+    left_column: Bits,
+    right_column: Bits,
+}
+
+impl Grid {
+    fn new(frame: Frame) -> Self {
+        let cols = frame.cols();
+        let left_column = (0..frame.rows()).fold(0, |bits, row| bits | 1 << (cols * row));
+        Grid { cols, left_column, right_column: left_column << (cols - 1) }
+    }
+
+    fn neighbours(&self, bits: Bits) -> Bits {
+        (bits << self.cols) | (bits >> self.cols) | ((bits & !self.right_column) << 1) | ((bits & !self.left_column) >> 1)
+    }
+
+    fn region(&self, seed: Bits, empty: Bits) -> Bits {
+        let mut region = seed;
+        loop {
+            let grown = (region | self.neighbours(region)) & empty;
+            if grown == region {
+                return region;
+            }
+            region = grown;
+        }
+    }
+}
+
+/// Squares covered by `placement`, if it lies within `frame`.
+pub fn placement_bits(placement: &Placement, frame: Frame) -> Option<Bits> {
+    let path = Path::from_orientation(placement.piece, placement.rotation, placement.mirror);
+    let mut square = Some(placement.square).filter(|&square| frame.contains(square))?;
+    let mut bits = square_bit(frame, square);
+    for &dir in path.iter() {
+        square = frame.step(square, dir)?;
+        bits |= square_bit(frame, square);
+    }
+    Some(bits)
+}
+
+/// Every distinct placement of `piece` within `frame`, along with the squares it covers.
+pub fn placements(piece: Piece, frame: Frame) -> Vec<(Placement, Bits)> {
+    let mut placements = Vec::new();
+    for (rotation, mirror) in piece.orientations() {
+        for square in frame.squares() {
+            let placement = Placement { piece, square, rotation, mirror };
+            if let Some(bits) = placement_bits(&placement, frame) {
+                placements.push((placement, bits));
+            }
+        }
+    }
+    placements
+}
+
+/// Outcome of a search limited in the number of placements it may try.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Search {
+    Found(Vec<Placement>),
+    NoSolution,
+    GaveUp,
+}
+
+#[derive(Debug)]
+pub struct Solver {
+    pieces: Vec<Piece>,
+    grid: Grid,
+    /// Placements by lowest square covered, then by position of the piece in `pieces`
+    candidates: Vec<Vec<Vec<(Placement, Bits)>>>,
+    /// Region sizes coverable by each subset of pieces, bit `n` standing for size `n`
+    fillable: Vec<u128>,
+}
+
+impl Solver {
+    /// A solver placing `pieces` within `frame`.
+    pub fn new(pieces: &[Piece], frame: Frame) -> Self {
+        let mut candidates = vec![vec![Vec::new(); pieces.len()]; Bits::BITS as usize];
+
+        for (i, &piece) in pieces.iter().enumerate() {
+            for (placement, mask) in placements(piece, frame) {
+                candidates[mask.trailing_zeros() as usize][i].push((placement, mask));
+            }
+        }
+
+        let mut fillable = vec![1; 1 << pieces.len()];
+        for remaining in 1..fillable.len() {
+            let piece = pieces[remaining.trailing_zeros() as usize];
+            let rest = fillable[remaining & (remaining - 1)];
+            fillable[remaining] = rest | rest << piece.square_count();
+        }
+
+        Solver { pieces: pieces.to_vec(), grid: Grid::new(frame), candidates, fillable }
+    }
+
+    /// Calls `found` for each way of placing every piece on the squares not in `occupied`,
+    /// leaving exactly one square uncovered in each of the (disjoint) `gaps`.
+    pub fn solve(&self, occupied: Bits, gaps: &[Bits], mut found: impl FnMut(&[Placement])) {
+        self.solve_until(occupied, gaps, |placements| {
+            found(placements);
+            ControlFlow::Continue(())
+        });
+    }
+
+    /// Calls `found` for each way of placing every piece as by [`Solver::solve`], stopping once it breaks.
+    pub fn solve_until(&self, occupied: Bits, gaps: &[Bits], mut found: impl FnMut(&[Placement]) -> ControlFlow<()>) {
+        let mut budget = usize::MAX;
+        self.start(occupied, gaps, &mut budget, &mut found);
+    }
+
+    /// Looks for one way of placing every piece as by [`Solver::solve`], giving up after trying `limit` placements.
+    pub fn find(&self, occupied: Bits, gaps: &[Bits], limit: usize) -> Search {
+        let mut budget = limit;
+        let mut solution = None;
+        self.start(occupied, gaps, &mut budget, &mut |placements| {
+            solution = Some(placements.to_vec());
+            ControlFlow::Break(())
+        });
+
+        match solution {
+            Some(placements) => Search::Found(placements),
+            None if budget == 0 => Search::GaveUp,
+            None => Search::NoSolution,
+        }
+    }
+
+    fn start(&self, occupied: Bits, gaps: &[Bits], budget: &mut usize, found: &mut impl FnMut(&[Placement]) -> ControlFlow<()>) {
+        let piece_squares: u32 = self.pieces.iter().map(|piece| piece.square_count() as u32).sum();
+        if (!occupied).count_ones() != piece_squares + gaps.len() as u32 {
+            return;
+        }
+
+        let remaining = (1 << self.pieces.len()) - 1;
+        let mut stack = Vec::with_capacity(self.pieces.len());
+        let _ = self.search(occupied, remaining, gaps, &mut stack, budget, found);
+    }
+
+    fn search(
+        &self,
+        occupied: Bits,
+        remaining: usize,
+        gaps: &[Bits],
+        stack: &mut Vec<Placement>,
+        budget: &mut usize,
+        found: &mut impl FnMut(&[Placement]) -> ControlFlow<()>,
+    ) -> ControlFlow<()> {
+        if remaining == 0 {
+            if gaps.iter().all(|&gap| (gap & !occupied).count_ones() == 1) {
+                return found(stack);
+            }
+            return ControlFlow::Continue(());
+        }
+
+        let cell = (!occupied).trailing_zeros();
+        if cell == Bits::BITS {
+            return ControlFlow::Continue(());
+        }
+
+        let mut pieces = remaining;
+        while pieces != 0 {
+            let piece = pieces & pieces.wrapping_neg();
+            pieces &= !piece;
+
+            for &(placement, mask) in self.candidates[cell as usize][piece.trailing_zeros() as usize].iter() {
+                if occupied & mask == 0 && self.can_fill(!(occupied | mask), self.grid.neighbours(mask), remaining & !piece, gaps) {
+                    if *budget == 0 {
+                        return ControlFlow::Break(());
+                    }
+                    *budget -= 1;
+
+                    stack.push(placement);
+                    let flow = self.search(occupied | mask, remaining & !piece, gaps, stack, budget, found);
+                    stack.pop();
+                    flow?;
+                }
+            }
+        }
+
+        if let Some(i) = gaps.iter().position(|&gap| gap & 1 << cell != 0) {
+            let rest = [&gaps[..i], &gaps[i+1..]].concat();
+            if self.can_fill(!(occupied | 1 << cell), !0, remaining, &rest) {
+                return self.search(occupied | 1 << cell, remaining, &rest, stack, budget, found);
+            }
+        }
+        ControlFlow::Continue(())
+    }
+
+    /// Whether each connected region of `empty` touching `near` could be covered by
+    /// the remaining pieces after leaving gaps.
+    fn can_fill(&self, empty: Bits, near: Bits, remaining: usize, gaps: &[Bits]) -> bool {
+        let mut rest = empty & near;
+        while rest != 0 {
+            let region = self.grid.region(rest & rest.wrapping_neg(), empty);
+            rest &= !region;
+
+            let size = region.count_ones();
+            let max_gaps = gaps.iter().filter(|&&gap| gap & region != 0).count() as u32;
+            let sizes = (1 << (max_gaps.min(size) + 1)) - 1;
+            if (self.fillable[remaining] >> (size - max_gaps.min(size))) & sizes == 0 {
+                return false;
+            }
+        }
+        true
+    }
+}
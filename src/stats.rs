@@ -0,0 +1,175 @@
+use crate::board::{Date, Square};
+use crate::export;
+use crate::solutions::SolutionIndex;
+use crate::render::Rgb;
+use crate::render::ansi::{self, Depth};
+
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use serde::Serialize;
+
+/// Background of dates with the fewest solutions in the heatmap.
+const FEWEST_COLOR: Rgb = Rgb(0xd9, 0x5a, 0x5a);
+/// Background of dates with the most solutions in the heatmap.
+const MOST_COLOR: Rgb = Rgb(0x98, 0xc3, 0x79);
+
+#[derive(Serialize, Clone, Debug)]
+pub struct DateCount {
+    #[serde(skip)]
+    date: Date,
+    month: String,
+    day: u8,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    weekday: Option<String>,
+    count: usize,
+}
+
+impl DateCount {
+    pub fn new(date: Date, count: usize) -> Self {
+        DateCount {
+            date,
+            month: date.month.to_string(),
+            day: date.day as u8 - Square::D01 as u8 + 1,
+            weekday: date.weekday.map(|weekday| weekday.to_string()),
+            count,
+        }
+    }
+
+    pub fn date(&self) -> Date {
+        self.date
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+}
+
+/// Number of solutions per date, summarized.
+#[derive(Serialize, Debug)]
+pub struct Stats {
+    pub total: usize,
+    /// Mean, median, fewest and most solutions per date, none if the frame has no valid dates.
+    pub mean: Option<f64>,
+    pub median: Option<f64>,
+    pub min: Option<usize>,
+    pub max: Option<usize>,
+    /// Dates with the fewest solutions, fewest first.
+    pub hardest: Vec<DateCount>,
+    /// Dates with the most solutions, most first.
+    pub easiest: Vec<DateCount>,
+    /// Every date of the frame, in order.
+    pub counts: Vec<DateCount>,
+}
+
+impl Stats {
+    /// Statistics of `solutions` over every date, and weekday if their frame has them, whose squares are all in the frame,
+    /// listing `top` of the hardest and easiest dates.
+    pub fn new(solutions: &SolutionIndex, top: usize) -> Self {
+        let frame = solutions.frame();
+        let counts = export::dates(frame, None, None).into_iter()
+            .filter(|date| date.squares().into_iter().all(|square| frame.contains(square)))
+            .map(|date| DateCount::new(date, solutions.count(&date)))
+            .collect::<Vec<_>>();
+
+        let mut sorted = counts.iter().map(|date| date.count).collect::<Vec<_>>();
+        sorted.sort_unstable();
+        let middle = sorted.len() / 2;
+        let median = match sorted.len() {
+            0 => None,
+            n if n % 2 == 0 => Some((sorted[middle - 1] + sorted[middle]) as f64 / 2.0),
+            _ => Some(sorted[middle] as f64),
+        };
+
+        let mut hardest = counts.clone();
+        hardest.sort_by_key(|date| (date.count, date.date));
+        let mut easiest = counts.clone();
+        easiest.sort_by_key(|date| (Reverse(date.count), date.date));
+
+        let total = sorted.iter().sum::<usize>();
+        Stats {
+            total,
+            mean: (!counts.is_empty()).then(|| total as f64 / counts.len() as f64),
+            median,
+            min: sorted.first().copied(),
+            max: sorted.last().copied(),
+            hardest: hardest.into_iter().take(top).collect(),
+            easiest: easiest.into_iter().take(top).collect(),
+            counts,
+        }
+    }
+
+    /// The statistics as text, with a heatmap of the counts colored if `depth` is given.
+    pub fn report(&self, depth: Option<Depth>) -> String {
+        let mut report = String::new();
+        let _ = writeln!(report, "{} solutions over {} dates", self.total, self.counts.len());
+        let (Some(mean), Some(median), Some(min), Some(max)) = (self.mean, self.median, self.min, self.max) else {
+            report.push_str("No date can be shown by the frame");
+            return report;
+        };
+        let _ = writeln!(report, "{mean:.1} per date on average, median {median}, fewest {min}, most {max}");
+
+        let _ = writeln!(report);
+        report.push_str(&self.heatmap(depth));
+
+        for (title, dates) in [("Hardest dates", &self.hardest), ("Easiest dates", &self.easiest)] {
+            if dates.is_empty() {
+                continue;
+            }
+            let _ = writeln!(report, "\n{title}:");
+            let width = dates.iter().map(|date| date.date.to_string().len()).max().unwrap_or(0);
+            for date in dates {
+                let _ = writeln!(report, "  {:<width$} {:>4}", date.date.to_string(), date.count);
+            }
+        }
+
+        report.trim_end().to_string()
+    }
+
+    /// Counts laid out as a calendar, with a row for each month and a column for each day,
+    /// colored by how they rank among the counts of all cells.
+    ///
+    /// Dates with weekdays share the cell of their month and day, which shows their counts summed over every weekday.
+    fn heatmap(&self, depth: Option<Depth>) -> String {
+        let mut cells = HashMap::<(Square, Square), usize>::new();
+        for date in self.counts.iter() {
+            *cells.entry((date.date.month, date.date.day)).or_default() += date.count;
+        }
+
+        let width = cells.values().max().map_or(0, |max| max.to_string().len()).max(3) + 1;
+        let mut heatmap = String::from("    ");
+        for day in 1..=31 {
+            let _ = write!(heatmap, "{day:>width$}");
+        }
+        heatmap.push('\n');
+
+        let mut sorted = cells.values().copied().collect::<Vec<_>>();
+        sorted.sort_unstable();
+        let rank = |count: usize| sorted.partition_point(|&other| other < count) as f32 / (sorted.len().max(2) - 1) as f32;
+
+        let squares = Square::squares();
+        let days = squares.iter().copied().filter(Square::is_day).collect::<Vec<_>>();
+        for month in squares.iter().copied().filter(Square::is_month) {
+            let Some(last) = days.iter().rposition(|&day| cells.contains_key(&(month, day))) else { continue; };
+            let _ = write!(heatmap, "{month} ");
+            for &day in days[..=last].iter() {
+                let Some(&count) = cells.get(&(month, day)) else {
+                    heatmap.push_str(&" ".repeat(width));
+                    continue;
+                };
+                let cell = format!("{count:>width$}");
+                match depth {
+                    Some(depth) => {
+                        let heat = FEWEST_COLOR.blend(MOST_COLOR, rank(count));
+                        heatmap.push_str(&ansi::paint(&cell, heat, depth));
+                    }
+                    None => heatmap.push_str(&cell),
+                }
+            }
+            heatmap.push('\n');
+        }
+
+        heatmap
+    }
+}
@@ -0,0 +1,17 @@
+use a_puzzle_a_day::board::Frame;
+use a_puzzle_a_day::calendar::Calendar;
+use a_puzzle_a_day::solutions::{self, SolutionIndex};
+
+#[test]
+fn counts_match_solution_file() {
+    let index = SolutionIndex::from_bytes(solutions::SOLUTIONS).unwrap();
+    let calendar = Calendar::solve(Frame::classic(), None);
+
+    assert_eq!(calendar.counts.len(), 366);
+    for date in calendar.counts.iter() {
+        assert_eq!(date.count(), index.count(&date.date()), "solutions for {} differ", date.date());
+    }
+    assert_eq!(calendar.total, calendar.counts.iter().map(|date| date.count()).sum::<usize>());
+    assert_eq!(calendar.distribution.iter().map(|bucket| bucket.dates).sum::<usize>(), 366);
+    assert!(calendar.unique.is_empty() && calendar.impossible.is_empty());
+}
@@ -0,0 +1,77 @@
+use a_puzzle_a_day::board::{Board, Date, DeadEnd, Frame, Piece, Placement, Square};
+use a_puzzle_a_day::solutions::{self, completion, solver, SolutionIndex};
+
+const LIMIT: usize = 1_000_000;
+
+fn date(month: Square, day: Square) -> Date {
+    Date { month, day, weekday: None }
+}
+
+/// Some placement of `piece` on the classic frame covering every one of `covered` and none of `free`.
+fn placement(piece: Piece, covered: &[Square], free: &[Square]) -> Placement {
+    let frame = Frame::classic();
+    solver::placements(piece, frame).into_iter()
+        .map(|(placement, _)| placement)
+        .find(|placement| {
+            let board = Board::new(frame).apply(placement).unwrap();
+            covered.iter().all(|&square| board.piece_at(square) == Some(piece))
+                && free.iter().all(|&square| board.piece_at(square).is_none())
+        })
+        .unwrap()
+}
+
+#[test]
+fn covered_date_is_a_dead_end() {
+    let board = Board::new(Frame::classic()).apply(&placement(Piece::I, &[Square::Feb], &[])).unwrap();
+    let date = date(Square::Feb, Square::D01);
+    assert_eq!(board.dead_end(date), Some(DeadEnd::DateCovered(Square::Feb)));
+    assert_eq!(board.is_completable(date, LIMIT), Some(false));
+    assert_eq!(completion(&board, date, LIMIT), Err(DeadEnd::DateCovered(Square::Feb)));
+}
+
+#[test]
+fn walled_off_square_is_a_dead_end() {
+    // With February shown, covering July leaves January on its own.
+    let date = date(Square::Feb, Square::D01);
+    let board = Board::new(Frame::classic())
+        .apply(&placement(Piece::I, &[Square::Jul], &[Square::Jan, Square::Feb, Square::D01]))
+        .unwrap();
+    assert_eq!(board.dead_end(date), Some(DeadEnd::Region(vec![Square::Jan])));
+    assert!(!board.is_completable_by_size(date));
+    assert_eq!(board.is_completable(date, LIMIT), Some(false));
+    assert_eq!(completion(&board, date, LIMIT), Err(DeadEnd::Region(vec![Square::Jan])));
+}
+
+#[test]
+fn partial_solution_is_completed() {
+    let index = SolutionIndex::from_bytes(solutions::SOLUTIONS).unwrap();
+    let date = date(Square::Mar, Square::D14);
+    let solution = index.get(&date).unwrap().remove(0);
+    let placements = solution.placements().unwrap();
+    let partial = placements[..placements.len() - 3].iter()
+        .fold(Board::new(Frame::classic()), |board, placement| board.apply(placement).unwrap());
+
+    assert_eq!(partial.dead_end(date), None);
+    assert_eq!(partial.is_completable(date, LIMIT), Some(true));
+    let completed = completion(&partial, date, LIMIT).unwrap().unwrap();
+    assert_eq!(completed.solved_for(), Some(date));
+    for square in Frame::classic().squares() {
+        if let Some(piece) = partial.piece_at(square) {
+            assert_eq!(completed.piece_at(square), Some(piece), "{piece} should stay on {square}");
+        }
+    }
+}
+
+#[test]
+fn dead_end_passing_size_check_has_no_solution() {
+    // Some single placement leaves regions that could be filled by size, yet no arrangement fills the board.
+    let date = date(Square::Jan, Square::D01);
+    let frame = Frame::classic();
+    let dead_end = frame.pieces().into_iter()
+        .flat_map(|piece| solver::placements(piece, frame))
+        .map(|(placement, _)| Board::new(frame).apply(&placement).unwrap())
+        .find(|board| board.dead_end(date).is_none() && completion(board, date, LIMIT) == Err(DeadEnd::NoSolution))
+        .expect("some placement should lead to no solution");
+    assert!(dead_end.is_completable_by_size(date));
+    assert_eq!(dead_end.is_completable(date, LIMIT), Some(false));
+}
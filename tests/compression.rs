@@ -0,0 +1,108 @@
+use a_puzzle_a_day::board::{Board, Date, Square};
+use a_puzzle_a_day::board::compact::CompactBoard;
+use a_puzzle_a_day::solutions::{self, apad, DataError, SolutionIndex};
+use a_puzzle_a_day::solutions::apad::IndexEntry;
+
+use std::sync::OnceLock;
+
+fn solutions() -> &'static Vec<Board> {
+    static BOARDS: OnceLock<Vec<Board>> = OnceLock::new();
+    BOARDS.get_or_init(|| {
+        let mut boards = solutions::read_boards(solutions::SOLUTIONS).unwrap();
+        boards.sort();
+        boards
+    })
+}
+
+fn encoded(compress: bool) -> &'static [u8] {
+    static PLAIN: OnceLock<Vec<u8>> = OnceLock::new();
+    static COMPRESSED: OnceLock<Vec<u8>> = OnceLock::new();
+    let cell = if compress { &COMPRESSED } else { &PLAIN };
+    cell.get_or_init(|| solutions::encode_boards(solutions().clone(), compress, None).unwrap())
+}
+
+#[test]
+fn compressed_round_trip() {
+    let boards = solutions();
+    let bytes = encoded(true);
+
+    let header = solutions::verify(bytes).unwrap();
+    assert_eq!(header.flags, apad::INDEXED | apad::COMPRESSED);
+    assert_eq!(header.count as usize, boards.len());
+
+    let mut decoded = solutions::read_boards(bytes).unwrap();
+    decoded.sort();
+    assert_eq!(&decoded, boards);
+}
+
+#[test]
+fn compressed_is_smaller() {
+    assert!(encoded(true).len() < encoded(false).len());
+}
+
+#[test]
+fn compressed_index_matches_plain() {
+    let plain = SolutionIndex::from_bytes(encoded(false)).unwrap();
+    let compressed = SolutionIndex::from_bytes(encoded(true)).unwrap();
+
+    let mut dates = plain.dates().collect::<Vec<_>>();
+    dates.sort();
+    let mut compressed_dates = compressed.dates().collect::<Vec<_>>();
+    compressed_dates.sort();
+    assert_eq!(dates, compressed_dates);
+
+    for date in dates {
+        let mut expected = plain.get(&date).unwrap();
+        let mut found = compressed.get(&date).unwrap();
+        expected.sort();
+        found.sort();
+        assert_eq!(compressed.count(&date), plain.count(&date));
+        assert_eq!(found, expected, "solutions for {date} differ");
+    }
+}
+
+#[test]
+fn plain_round_trip() {
+    let boards = solutions();
+    let bytes = encoded(false);
+    assert_eq!(solutions::verify(bytes).unwrap().flags, apad::INDEXED);
+
+    let mut decoded = solutions::read_boards(bytes).unwrap();
+    decoded.sort();
+    assert_eq!(&decoded, boards);
+}
+
+#[test]
+fn corrupted_compressed_file_is_rejected() {
+    let date = Date { month: Square::Feb, day: Square::D29, weekday: None };
+    let boards = SolutionIndex::from_bytes(solutions::SOLUTIONS).unwrap().get(&date).unwrap();
+    let mut bytes = solutions::encode_boards(boards, true, None).unwrap();
+
+    let last = bytes.len() - 1;
+    bytes[last] ^= 0xFF;
+    assert!(matches!(solutions::read_boards(&bytes), Err(DataError::ChecksumMismatch { .. })));
+
+    bytes.truncate(last);
+    assert!(solutions::read_boards(&bytes).is_err());
+}
+
+#[test]
+fn index_not_covering_records_is_rejected() {
+    let feb = |day| Date { month: Square::Feb, day, weekday: None };
+    let index = SolutionIndex::from_bytes(solutions::SOLUTIONS).unwrap();
+    let (first, second) = (index.get(&feb(Square::D28)).unwrap(), index.get(&feb(Square::D29)).unwrap());
+    let frame = first[0].frame();
+    let size = apad::record_size(frame);
+
+    let mut records = Vec::new();
+    for board in first.iter().chain(second.iter()) {
+        records.extend(CompactBoard::try_from(board.clone()).unwrap().to_bytes());
+    }
+    // The second date starts on the last board of the first, leaving the final record unreachable.
+    let entries = [
+        IndexEntry { date: feb(Square::D28), offset: 0, count: first.len() as u32 },
+        IndexEntry { date: feb(Square::D29), offset: ((first.len() - 1) * size) as u32, count: second.len() as u32 },
+    ];
+    let bytes = apad::encode(frame, Some(&entries), false, &records);
+    assert!(matches!(solutions::verify(&bytes), Err(DataError::InvalidIndex)));
+}
@@ -0,0 +1,64 @@
+use a_puzzle_a_day::board::{Board, Path, Piece, Placement, Rotation};
+use a_puzzle_a_day::browse::filter::Filter;
+use a_puzzle_a_day::solutions::{self, SolutionIndex};
+
+fn all_orientations() -> impl Iterator<Item = (Rotation, bool)> {
+    [false, true].into_iter().flat_map(|mirror| (0..4).map(move |amount| (Rotation::from(amount), mirror)))
+}
+
+fn orientation(rotation: Rotation, mirror: bool) -> String {
+    format!("{}{}", rotation as u8, if mirror { "m" } else { "" })
+}
+
+fn solution() -> Board {
+    let index = SolutionIndex::from_bytes(solutions::SOLUTIONS).unwrap();
+    let date = index.dates().min().unwrap();
+    index.get(&date).unwrap().remove(0)
+}
+
+fn placed(board: &Board, piece: Piece) -> Placement {
+    board.placements().unwrap().into_iter().find(|placement| placement.piece == piece).unwrap()
+}
+
+#[test]
+fn symmetric_orientations_parse() {
+    for filter in ["O@Jan:2", "U:0m", "Z:3m", "O:1"] {
+        assert!(filter.parse::<Filter>().is_ok(), "`{filter}` should parse");
+    }
+    assert!("O@Jan:4".parse::<Filter>().is_err());
+}
+
+#[test]
+fn orientations_match_by_footprint() {
+    let board = solution();
+    for piece in board.frame().pieces() {
+        let placed = placed(&board, piece);
+        let footprint = |(rotation, mirror)| Path::from_orientation(piece, rotation, mirror).footprint();
+        let mut matching = 0;
+        for (rotation, mirror) in all_orientations() {
+            let filter = format!("{}:{}", piece.letter(), orientation(rotation, mirror)).parse::<Filter>().unwrap();
+            let same = footprint((rotation, mirror)) == footprint((placed.rotation, placed.mirror));
+            assert_eq!(filter.matches(&board), same, "{filter} on {piece}");
+            matching += same as usize;
+        }
+        assert_eq!(matching, 8 / piece.orientations().len(), "orientations of the {piece} like the one placed");
+    }
+}
+
+#[test]
+fn placements_match_by_squares_covered() {
+    let board = solution();
+    for piece in board.frame().pieces() {
+        let alone = Board::new(board.frame()).apply(&placed(&board, piece)).unwrap();
+        let mut matching = 0;
+        for square in board.frame().squares() {
+            for (rotation, mirror) in all_orientations() {
+                let filter = format!("{}@{square}:{}", piece.letter(), orientation(rotation, mirror)).parse::<Filter>().unwrap();
+                let covers = Board::new(board.frame()).apply(&Placement { piece, square, rotation, mirror }) == Some(alone.clone());
+                assert_eq!(filter.matches(&board), covers, "{filter} on {piece}");
+                matching += covers as usize;
+            }
+        }
+        assert_eq!(matching, 8 / piece.orientations().len(), "placements of the {piece} like the one placed");
+    }
+}
@@ -0,0 +1,21 @@
+use a_puzzle_a_day::board::{Frame, Piece};
+
+#[test]
+fn loading_a_frame_again_reuses_it() {
+    let frame = Frame::weekday().to_string().parse::<Frame>().unwrap();
+    assert_eq!(frame, Frame::weekday());
+    assert!(std::ptr::eq(frame.name(), Frame::weekday().name()));
+
+    let mut pieces = Frame::classic().pieces();
+    pieces.reverse();
+    let first = Frame::classic().with_pieces(pieces.clone()).unwrap();
+    let second = Frame::classic().with_pieces(pieces).unwrap();
+    assert!(std::ptr::eq(first.name(), second.name()));
+    assert_eq!(first.pieces().first(), Some(&Piece::Z));
+}
+
+#[test]
+fn frame_without_pieces_is_rejected() {
+    let err = "pieces\nJan 1".parse::<Frame>().unwrap_err();
+    assert!(err.to_string().contains("at least one piece"), "{err}");
+}
@@ -0,0 +1,20 @@
+use a_puzzle_a_day::board::Frame;
+use a_puzzle_a_day::solutions::{self, SolutionIndex};
+
+#[test]
+fn generated_solutions_match_included() {
+    let generated = solutions::generate(Frame::classic(), None);
+    let included = SolutionIndex::from_bytes(solutions::SOLUTIONS).unwrap();
+    assert_eq!(generated.total(), included.total());
+
+    for date in included.dates() {
+        let mut expected = included.get(&date).unwrap();
+        let mut found = generated.get(&date).unwrap();
+        expected.sort();
+        found.sort();
+        assert_eq!(found, expected, "solutions for {date} differ");
+    }
+
+    let bytes = solutions::encode_index(&generated, true, None).unwrap();
+    assert_eq!(SolutionIndex::from_bytes(&bytes).unwrap().total(), included.total());
+}
@@ -0,0 +1,60 @@
+use a_puzzle_a_day::board::{Board, Date, Frame};
+use a_puzzle_a_day::cli::Format;
+use a_puzzle_a_day::export::export;
+use a_puzzle_a_day::import::import;
+use a_puzzle_a_day::solutions::{self, SolutionIndex};
+
+fn solutions() -> Vec<(Date, Board)> {
+    let index = SolutionIndex::from_bytes(solutions::SOLUTIONS).unwrap();
+    index.dates().take(3)
+        .flat_map(|date| index.get(&date).unwrap().into_iter().take(2).map(move |board| (date, board)))
+        .collect()
+}
+
+fn exported(format: Format, solutions: &[(Date, Board)]) -> String {
+    let mut out = Vec::new();
+    export(&mut out, format, solutions).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+fn round_trip(format: Format) {
+    let solutions = solutions();
+    let boards = import(&exported(format, &solutions), format, Frame::default()).unwrap();
+    assert_eq!(boards, solutions.into_iter().map(|(_, board)| board).collect::<Vec<_>>());
+}
+
+#[test]
+fn json_round_trip() {
+    round_trip(Format::Json);
+}
+
+#[test]
+fn ndjson_round_trip() {
+    round_trip(Format::Ndjson);
+}
+
+#[test]
+fn csv_round_trip() {
+    round_trip(Format::Csv);
+}
+
+#[test]
+fn csv_rows_with_trailing_spaces_stripped() {
+    let solutions = solutions();
+    let csv = exported(Format::Csv, &solutions);
+    let stripped = csv.lines().map(|line| line.replace(" /", "/")).collect::<Vec<_>>().join("\n");
+    assert_ne!(stripped.trim_end(), csv.trim_end());
+    let boards = import(&stripped, Format::Csv, Frame::default()).unwrap();
+    assert_eq!(boards.len(), solutions.len());
+}
+
+#[test]
+fn grid_not_matching_placements_is_rejected() {
+    let solutions = solutions();
+    let csv = exported(Format::Csv, &solutions[..1]);
+    let (rest, grid) = csv.trim_end().rsplit_once(',').unwrap();
+    let swapped = grid.chars().map(|c| match c { 'L' => 'Z', 'Z' => 'L', c => c }).collect::<String>();
+    let swapped = format!("{rest},{swapped}");
+    assert_ne!(swapped, csv.trim_end());
+    assert!(import(&swapped, Format::Csv, Frame::default()).is_err());
+}
@@ -0,0 +1,72 @@
+use a_puzzle_a_day::board::{Path, Piece, Rotation};
+
+fn all_orientations() -> impl Iterator<Item = (Rotation, bool)> {
+    [false, true].into_iter().flat_map(|mirror| (0..4).map(move |amount| (Rotation::from(amount), mirror)))
+}
+
+#[test]
+fn orientations_match_derived() {
+    for piece in Piece::pieces() {
+        assert_eq!(piece.orientations(), piece.distinct_orientations(), "orientations of the {piece} differ");
+    }
+}
+
+#[test]
+fn symmetries_match_derived() {
+    for piece in Piece::pieces() {
+        let path = Path::from(piece);
+        assert_eq!(piece.rotational_symmetry(), path.rotational_symmetry(), "rotational symmetry of the {piece}");
+        assert_eq!(piece.mirror_symmetric(), path.mirror_symmetric(), "mirror symmetry of the {piece}");
+    }
+}
+
+#[test]
+fn rotations_by_symmetry_are_distinct() {
+    for piece in Piece::pieces() {
+        let path = Path::from(piece);
+        let mut footprints = Rotation::all_by_symmetry(piece.rotational_symmetry()).into_iter()
+            .map(|rotation| path.rotate(rotation).footprint())
+            .collect::<Vec<_>>();
+        let count = footprints.len();
+        footprints.sort();
+        footprints.dedup();
+        assert_eq!(footprints.len(), count, "turns of the {piece} repeat");
+        assert_eq!(count * piece.rotational_symmetry() as usize, 4, "turns of the {piece} are missing");
+    }
+}
+
+#[test]
+fn orientations_cover_every_footprint() {
+    for piece in Piece::pieces() {
+        let footprint = |(rotation, mirror)| Path::from_orientation(piece, rotation, mirror).footprint();
+        let kept = piece.orientations().into_iter().map(footprint).collect::<Vec<_>>();
+        for orientation in all_orientations() {
+            assert!(kept.contains(&footprint(orientation)), "{piece} misses {orientation:?}");
+        }
+    }
+}
+
+#[test]
+fn paths_compare_by_steps() {
+    for piece in Piece::pieces() {
+        let path = Path::from(piece);
+        assert_eq!(path.rotate(Rotation::Half).rotate(Rotation::Half), path);
+        assert_eq!(path.mirror().mirror(), path);
+        assert_eq!(path.footprint().len(), piece.square_count());
+    }
+    assert_ne!(Path::from(Piece::L), Path::from(Piece::J));
+}
+
+#[test]
+fn defined_pieces_derive_symmetries() {
+    let x = Piece::define('X', &[(0, 1), (1, 0), (1, 1), (1, 2), (2, 1)]).unwrap();
+    assert_eq!((x.rotational_symmetry(), x.mirror_symmetric()), (4, true));
+    assert_eq!(x.orientations(), x.distinct_orientations());
+
+    let f = Piece::define('F', &[(0, 1), (0, 2), (1, 0), (1, 1), (2, 1)]).unwrap();
+    assert_eq!((f.rotational_symmetry(), f.mirror_symmetric()), (1, false));
+    assert_eq!(f.orientations().len(), 8);
+
+    let l = Piece::define('L', &[(0, 0), (0, 1), (0, 2), (0, 3), (1, 0)]).unwrap();
+    assert_eq!(l, Piece::L);
+}
@@ -0,0 +1,25 @@
+use a_puzzle_a_day::board::Frame;
+use a_puzzle_a_day::solutions::{self, SolutionIndex};
+use a_puzzle_a_day::stats::Stats;
+
+#[test]
+fn stats_of_included_solutions() {
+    let stats = Stats::new(&SolutionIndex::from_bytes(solutions::SOLUTIONS).unwrap(), 3);
+    assert_eq!(stats.counts.len(), 366);
+    assert_eq!(stats.total, 24405);
+    assert_eq!((stats.min, stats.max), (Some(7), Some(216)));
+    assert_eq!(stats.median, Some(59.0));
+    assert_eq!(stats.hardest.len(), 3);
+}
+
+#[test]
+fn stats_of_frame_without_dates() {
+    let frame = "piece M 0,0\npieces M\nFeb 30 31".parse::<Frame>().unwrap();
+    let stats = Stats::new(&solutions::generate(frame, None), 10);
+
+    assert_eq!(stats.total, 0);
+    assert!(stats.counts.is_empty() && stats.hardest.is_empty());
+    assert_eq!((stats.mean, stats.median, stats.min, stats.max), (None, None, None, None));
+    assert!(stats.report(None).starts_with("0 solutions over 0 dates"));
+    assert!(serde_json::to_string(&stats).is_ok());
+}